@@ -1,9 +1,93 @@
 use crate::prelude::*;
 use std::collections::HashMap;
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Separator {
+    /// Any run of spaces and tabs.
+    Whitespace,
+    /// A single character such as `,` or `;`; whitespace around the values is ignored.
+    Char(char),
+}
+
+impl Separator {
+    /// Guesses the separator from a line of input: a comma or semicolon if there is one,
+    /// whitespace otherwise.
+    fn detect(line: &str) -> Self {
+        [',', ';']
+            .into_iter()
+            .find(|&c| line.contains(c))
+            .map_or(Self::Whitespace, Self::Char)
+    }
+
+    fn split<'a>(self, line: &'a str) -> Box<dyn Iterator<Item = &'a str> + 'a> {
+        match self {
+            Self::Whitespace => Box::new(line.split_whitespace()),
+            Self::Char(c) => Box::new(line.split(c).map(str::trim)),
+        }
+    }
+}
+
 pub struct Input {
-    left: Vec<i32>,
-    right: Vec<i32>,
+    columns: Vec<Vec<i32>>,
+}
+
+impl Input {
+    /// Reads rows of numbers line by line, splitting them on `separator`, or on whatever the
+    /// first row looks like it's using if there is none. Every row must have as many values as
+    /// the first one.
+    pub fn parse<B: BufRead>(reader: B, separator: Option<Separator>) -> Result<Self> {
+        let mut separator = separator;
+        let mut columns: Vec<Vec<i32>> = Vec::new();
+
+        for (i, line) in reader.lines().enumerate() {
+            let line = line?;
+
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let separator = *separator.get_or_insert_with(|| Separator::detect(&line));
+
+            let row = separator
+                .split(&line)
+                .map(|value| {
+                    value
+                        .parse()
+                        .map_err(|e| format!("line {}: invalid value {value:?}: {e}", i + 1))
+                })
+                .collect::<std::result::Result<Vec<i32>, _>>()?;
+
+            if columns.is_empty() {
+                columns = vec![Vec::new(); row.len()];
+            }
+
+            if row.len() != columns.len() {
+                return Err(format!(
+                    "line {}: expected {} columns, found {}",
+                    i + 1,
+                    columns.len(),
+                    row.len()
+                )
+                .into());
+            }
+
+            for (column, n) in columns.iter_mut().zip(row) {
+                column.push(n);
+            }
+        }
+
+        Ok(Self { columns })
+    }
+
+    /// The first two columns, which are the ones the puzzle is about.
+    fn pair(self) -> Result<(Vec<i32>, Vec<i32>)> {
+        let mut columns = self.columns.into_iter();
+
+        match (columns.next(), columns.next()) {
+            (Some(left), Some(right)) => Ok((left, right)),
+            _ => Err("input needs at least two columns".into()),
+        }
+    }
 }
 
 pub struct A;
@@ -16,7 +100,9 @@ impl Puzzle for A {
         let left = vec![3, 4, 2, 1, 3, 3];
         let right = vec![4, 3, 5, 3, 9, 3];
 
-        Input { left, right }
+        Input {
+            columns: vec![left, right],
+        }
     }
 
     fn example_output() -> Self::Output {
@@ -28,29 +114,11 @@ impl Puzzle for A {
     }
 
     fn parse_input<B: BufRead>(reader: B) -> Result<Self::Input> {
-        let mut left = Vec::new();
-        let mut right = Vec::new();
-
-        for line in reader.lines() {
-            let line = line?;
-
-            let mut split = line.split_whitespace();
-
-            let n = split.next().ok_or("invalid input")?.parse()?;
-            left.push(n);
-
-            let n = split.next().ok_or("invalid input")?.parse()?;
-            right.push(n);
-        }
-
-        Ok(Input { left, right })
+        Input::parse(reader, None)
     }
 
     fn solve(&mut self, input: Self::Input) -> Result<Self::Output> {
-        let Input {
-            mut left,
-            mut right,
-        } = input;
+        let (mut left, mut right) = input.pair()?;
 
         left.sort();
         right.sort();
@@ -65,9 +133,60 @@ impl Puzzle for A {
 
 pub struct B;
 
+impl B {
+    /// Similarity score using a frequency table of the right list; linear in the input size.
+    fn similarity(left: &[i32], right: &[i32]) -> i64 {
+        let mut counts = HashMap::new();
+
+        for &y in right {
+            *counts.entry(y).or_insert(0) += 1;
+        }
+
+        left.iter()
+            .map(|x| *x as i64 * counts.get(x).copied().unwrap_or(0))
+            .sum()
+    }
+
+    /// Similarity score by walking both lists in sorted order. Avoiding hashing makes it about
+    /// twice as fast as the frequency table in `b_benchmark` up to 100k lines, and about even at
+    /// a million; it has to take and sort both lists though, so it's only kept for comparison.
+    #[cfg(test)]
+    fn similarity_sorted(mut left: Vec<i32>, mut right: Vec<i32>) -> i64 {
+        left.sort_unstable();
+        right.sort_unstable();
+
+        let mut score = 0;
+        let mut j = 0;
+        let mut previous = None;
+        let mut count = 0;
+
+        for &x in &left {
+            // runs of equal values on the left reuse the count of the first one
+            if previous != Some(x) {
+                while j < right.len() && right[j] < x {
+                    j += 1;
+                }
+
+                count = 0;
+
+                while j < right.len() && right[j] == x {
+                    count += 1;
+                    j += 1;
+                }
+
+                previous = Some(x);
+            }
+
+            score += x as i64 * count;
+        }
+
+        score
+    }
+}
+
 impl Puzzle for B {
     type Input = Input;
-    type Output = i32;
+    type Output = i64;
 
     fn example_input() -> Self::Input {
         A::example_input()
@@ -86,22 +205,16 @@ impl Puzzle for B {
     }
 
     fn solve(&mut self, input: Self::Input) -> Result<Self::Output> {
-        let Input { left, right } = input;
-
-        let mut counts = HashMap::new();
+        let (left, right) = input.pair()?;
 
-        for x in left.iter() {
-            let count = right.iter().filter(|&y| y == x).count() as i32;
-            *counts.entry(x).or_insert(0) += x * count;
-        }
-
-        Ok(counts.values().sum())
+        Ok(Self::similarity(&left, &right))
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::testing::{time, Random};
 
     #[test]
     fn a() -> Result<()> {
@@ -109,9 +222,103 @@ mod tests {
         a.test_example()
     }
 
+    fn parse(s: &str, separator: Option<Separator>) -> Result<Input> {
+        Input::parse(s.as_bytes(), separator)
+    }
+
+    #[test]
+    fn formats() -> Result<()> {
+        let expected = vec![vec![3, 4], vec![4, 3]];
+
+        assert_eq!(parse("3   4\n4   3\n", None)?.columns, expected);
+        assert_eq!(parse("3\t4\n4\t3", None)?.columns, expected);
+        assert_eq!(parse("3, 4\n4,3\n\n", None)?.columns, expected);
+        assert_eq!(
+            parse("3;4\n4;3", Some(Separator::Char(';')))?.columns,
+            expected
+        );
+
+        let three = parse("1 2 3\n4 5 6", None)?;
+        assert_eq!(three.columns, vec![vec![1, 4], vec![2, 5], vec![3, 6]]);
+        assert_eq!(A.solve(three)?, 2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn malformed() {
+        assert!(parse("1 2\n3 4 5", None).is_err());
+        assert!(parse("1 2\n3", None).is_err());
+        assert!(parse("1,2\n3 4", None).is_err());
+        assert!(parse("1 x", None).is_err());
+        assert!(A.solve(parse("1\n2", None).unwrap()).is_err());
+    }
+
     #[test]
     fn b() -> Result<()> {
         let mut b = B;
         b.test_example()
     }
+
+    /// The original quadratic implementation, kept to check the faster ones against.
+    fn similarity_reference(left: &[i32], right: &[i32]) -> i64 {
+        let mut counts = HashMap::new();
+
+        for x in left.iter() {
+            let count = right.iter().filter(|&y| y == x).count() as i64;
+            *counts.entry(x).or_insert(0) += *x as i64 * count;
+        }
+
+        counts.values().sum()
+    }
+
+    /// Two columns of `lines` pseudo-random numbers below `max`.
+    fn generate(lines: usize, max: u32) -> (Vec<i32>, Vec<i32>) {
+        let mut random = Random::new(0x5eed);
+        let mut random = move || random.below(max as u64) as i32;
+
+        let mut left = Vec::with_capacity(lines);
+        let mut right = Vec::with_capacity(lines);
+
+        for _ in 0..lines {
+            left.push(random());
+            right.push(random());
+        }
+
+        (left, right)
+    }
+
+    #[test]
+    fn b_differential() {
+        // small value ranges make sure there are plenty of repeats on both sides
+        for (lines, max) in [(0, 1), (1, 1), (10, 3), (1000, 50), (2000, 100000)] {
+            let (left, right) = generate(lines, max);
+            let expected = similarity_reference(&left, &right);
+
+            assert_eq!(B::similarity(&left, &right), expected);
+            assert_eq!(B::similarity_sorted(left, right), expected);
+        }
+    }
+
+    #[test]
+    #[ignore]
+    fn b_benchmark() {
+        for lines in [10_000, 100_000, 1_000_000] {
+            let (left, right) = generate(lines, 100000);
+
+            let (table, table_time) = time(|| B::similarity(&left, &right));
+            let (sorted, sorted_time) = time(|| B::similarity_sorted(left.clone(), right.clone()));
+
+            assert_eq!(table, sorted);
+
+            // the quadratic reference takes too long beyond this
+            if lines <= 100_000 {
+                let (reference, reference_time) = time(|| similarity_reference(&left, &right));
+                assert_eq!(reference, table);
+                println!("{lines:>9} lines: reference {reference_time:?}");
+            }
+
+            println!("{lines:>9} lines: table {table_time:?}, sorted {sorted_time:?}");
+        }
+    }
 }
@@ -1,32 +1,220 @@
 use crate::prelude::*;
+use std::fmt;
+use std::ops::RangeInclusive;
+
+/// Decides whether a report is safe: its levels all increase or all decrease, by a step within
+/// `steps`, once at most `max_removals` levels are taken out.
+pub struct Dampener {
+    max_removals: usize,
+    steps: RangeInclusive<i32>,
+}
 
-pub struct A;
+impl Dampener {
+    pub fn new(max_removals: usize, steps: RangeInclusive<i32>) -> Self {
+        Self {
+            max_removals,
+            steps,
+        }
+    }
+
+    /// Indices of the fewest levels that have to be removed to make `report` safe, if that's at
+    /// most `max_removals`; with more than that, it's only some set of removals that works.
+    ///
+    /// For each level, tracks the fewest removals needed for a safe run ending in that level, and
+    /// the level before it in that run. Only the `max_removals + 1` levels before it can precede
+    /// it without going over the limit, so this is linear in the length of the report.
+    fn removals(&self, report: &[i32]) -> Vec<usize> {
+        let reach = self.max_removals + 1;
+
+        // removing every level always works
+        let mut best = (report.len(), None, Vec::new());
+
+        for increasing in [true, false] {
+            let mut removals = vec![0; report.len()];
+            let mut previous = vec![None; report.len()];
+            let mut end = None;
+            let mut fewest = best.0;
+
+            for i in 0..report.len() {
+                // drop everything before this level
+                removals[i] = i;
+
+                for j in i.saturating_sub(reach)..i {
+                    let step = if increasing {
+                        report[i] - report[j]
+                    } else {
+                        report[j] - report[i]
+                    };
+
+                    // on ties, keep the nearest level, so the earlier ones are the ones removed
+                    if self.steps.contains(&step) && removals[j] + (i - j - 1) <= removals[i] {
+                        removals[i] = removals[j] + (i - j - 1);
+                        previous[i] = Some(j);
+                    }
+                }
+
+                // drop everything after this level
+                let total = removals[i] + (report.len() - 1 - i);
+
+                if total < fewest {
+                    fewest = total;
+                    end = Some(i);
+                }
+            }
+
+            if end.is_some() {
+                best = (fewest, end, previous);
+            }
+        }
+
+        let (_, end, previous) = best;
+        let mut kept = vec![false; report.len()];
+        let mut level = end;
+
+        while let Some(i) = level {
+            kept[i] = true;
+            level = previous[i];
+        }
+
+        (0..report.len()).filter(|&i| !kept[i]).collect()
+    }
 
-impl A {
-    fn is_safe(report: &[i32]) -> bool {
-        let direction = report[0] > report[1];
+    pub fn is_safe(&self, report: &[i32]) -> bool {
+        // a report of one level or none is trivially safe
+        report.len() <= 1 || self.removals(report).len() <= self.max_removals
+    }
+
+    /// The first pair of adjacent levels that breaks the rules, if no levels are removed. The
+    /// direction is set by the first pair, like the puzzle describes it.
+    fn first_failure(&self, report: &[i32]) -> Option<Failure> {
+        let increasing = report.len() > 1 && report[1] > report[0];
 
         for i in 1..report.len() {
-            let a = report[i - 1];
-            let b = report[i];
+            let step = report[i] - report[i - 1];
+
+            let problem = if step == 0 {
+                Problem::EqualLevels
+            } else if (step > 0) != increasing {
+                Problem::DirectionChange
+            } else if step.abs() > *self.steps.end() {
+                Problem::StepTooLarge
+            } else if step.abs() < *self.steps.start() {
+                Problem::StepTooSmall
+            } else {
+                continue;
+            };
+
+            return Some(Failure { index: i, problem });
+        }
 
-            if (a > b) != direction {
-                return false;
-            }
+        None
+    }
 
-            if a == b {
-                return false;
-            }
+    /// Explains why `report` is or isn't safe, and which levels, at most `max_removals` of them,
+    /// could be removed to fix it.
+    pub fn diagnose(&self, report: &[i32]) -> Diagnosis {
+        let failure = self.first_failure(report);
+
+        let fix = failure.and_then(|_| {
+            let removals = self.removals(report);
+            (removals.len() <= self.max_removals).then_some(removals)
+        });
+
+        Diagnosis {
+            levels: report.to_vec(),
+            max_removals: self.max_removals,
+            failure,
+            fix,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Problem {
+    DirectionChange,
+    EqualLevels,
+    StepTooLarge,
+    StepTooSmall,
+}
+
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s = match self {
+            Self::DirectionChange => "direction change",
+            Self::EqualLevels => "equal levels",
+            Self::StepTooLarge => "step too large",
+            Self::StepTooSmall => "step too small",
+        };
+
+        f.write_str(s)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Failure {
+    /// Index of the second level of the offending pair.
+    index: usize,
+    problem: Problem,
+}
+
+pub struct Diagnosis {
+    levels: Vec<i32>,
+    max_removals: usize,
+    failure: Option<Failure>,
+    /// Indices of the levels to remove, in order.
+    fix: Option<Vec<usize>>,
+}
 
-            if (a - b).abs() > 3 {
-                return false;
+impl fmt::Display for Diagnosis {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}: ", self.levels)?;
+
+        let Some(Failure { index, problem }) = self.failure else {
+            return write!(f, "safe");
+        };
+
+        write!(
+            f,
+            "unsafe at index {index} ({} -> {}, {problem})",
+            self.levels[index - 1],
+            self.levels[index]
+        )?;
+
+        match (&self.fix, self.max_removals) {
+            (Some(fix), _) => {
+                let plural = if fix.len() == 1 { "" } else { "s" };
+                write!(f, ", safe after removing level{plural} ")?;
+
+                for (n, &i) in fix.iter().enumerate() {
+                    if n > 0 {
+                        write!(f, ", ")?;
+                    }
+
+                    write!(f, "{i} ({})", self.levels[i])?;
+                }
+
+                Ok(())
             }
+            (None, 0) => Ok(()),
+            (None, 1) => write!(f, ", no single removal fixes it"),
+            (None, k) => write!(f, ", no {k} removals fix it"),
         }
-
-        true
     }
 }
 
+/// Diagnoses every report in the puzzle input.
+pub fn explain() -> Result<Vec<Diagnosis>> {
+    let reports = A::parse_input(A::load_input()?)?;
+    let dampener = Dampener::new(1, 1..=3);
+
+    Ok(reports
+        .iter()
+        .map(|report| dampener.diagnose(report))
+        .collect())
+}
+
+pub struct A;
+
 impl Puzzle for A {
     type Input = Vec<Vec<i32>>;
     type Output = usize;
@@ -64,45 +252,27 @@ impl Puzzle for A {
                 levels.push(level.parse()?);
             }
 
-            reports.push(levels)
+            // blank lines aren't reports, and would count as safe ones
+            if !levels.is_empty() {
+                reports.push(levels)
+            }
         }
 
         Ok(reports)
     }
 
     fn solve(&mut self, reports: Self::Input) -> Result<Self::Output> {
-        Ok(reports.iter().filter(|report| A::is_safe(report)).count())
+        let dampener = Dampener::new(0, 1..=3);
+
+        Ok(reports
+            .iter()
+            .filter(|report| dampener.is_safe(report))
+            .count())
     }
 }
 
 pub struct B;
 
-impl B {
-    fn is_safe(report: &[i32]) -> bool {
-        if A::is_safe(report) {
-            return true;
-        }
-
-        let mut v = Vec::with_capacity(report.len() - 1);
-
-        for i in 0..report.len() {
-            let first_half = &report[..i];
-            let second_half = &report[i + 1..];
-
-            v.extend_from_slice(first_half);
-            v.extend_from_slice(second_half);
-
-            if A::is_safe(&v) {
-                return true;
-            }
-
-            v.clear();
-        }
-
-        false
-    }
-}
-
 impl Puzzle for B {
     type Input = Vec<Vec<i32>>;
     type Output = usize;
@@ -127,13 +297,19 @@ impl Puzzle for B {
     }
 
     fn solve(&mut self, reports: Self::Input) -> Result<Self::Output> {
-        Ok(reports.iter().filter(|report| B::is_safe(report)).count())
+        let dampener = Dampener::new(1, 1..=3);
+
+        Ok(reports
+            .iter()
+            .filter(|report| dampener.is_safe(report))
+            .count())
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::testing::{time, Random};
 
     #[test]
     fn a() -> Result<()> {
@@ -146,4 +322,125 @@ mod tests {
         let mut b = B;
         b.test_example()
     }
+
+    #[test]
+    fn blank_lines() -> Result<()> {
+        let reports = A::parse_input("1 2 3\n\n3 2 1\n   \n".as_bytes())?;
+        assert_eq!(reports, vec![vec![1, 2, 3], vec![3, 2, 1]]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn diagnoses() {
+        let dampener = Dampener::new(1, 1..=3);
+        let diagnoses: Vec<_> = A::example_input()
+            .iter()
+            .map(|report| dampener.diagnose(report))
+            .collect();
+
+        let failure = |index, problem| Some(Failure { index, problem });
+
+        assert_eq!(diagnoses[0].failure, None);
+        assert_eq!(diagnoses[1].failure, failure(2, Problem::StepTooLarge));
+        assert_eq!(diagnoses[2].failure, failure(3, Problem::StepTooLarge));
+        assert_eq!(diagnoses[3].failure, failure(2, Problem::DirectionChange));
+        assert_eq!(diagnoses[4].failure, failure(3, Problem::EqualLevels));
+        assert_eq!(diagnoses[5].failure, None);
+
+        let fixes: Vec<_> = diagnoses.iter().map(|d| d.fix.clone()).collect();
+        assert_eq!(
+            fixes,
+            vec![None, None, None, Some(vec![1]), Some(vec![2]), None]
+        );
+
+        assert_eq!(
+            diagnoses[3].to_string(),
+            "[1, 3, 2, 4, 5]: unsafe at index 2 (3 -> 2, direction change), \
+             safe after removing level 1 (3)"
+        );
+
+        let report = [1, 3, 2, 2, 4];
+        assert_eq!(
+            dampener.diagnose(&report).to_string(),
+            "[1, 3, 2, 2, 4]: unsafe at index 2 (3 -> 2, direction change), \
+             no single removal fixes it"
+        );
+        assert_eq!(
+            Dampener::new(2, 1..=3).diagnose(&report).to_string(),
+            "[1, 3, 2, 2, 4]: unsafe at index 2 (3 -> 2, direction change), \
+             safe after removing levels 1 (3), 2 (2)"
+        );
+    }
+
+    /// Tries every way of removing up to `max_removals` levels.
+    fn is_safe_reference(report: &[i32], max_removals: usize) -> bool {
+        let monotonic = report.windows(2).all(|w| (1..=3).contains(&(w[1] - w[0])))
+            || report.windows(2).all(|w| (1..=3).contains(&(w[0] - w[1])));
+
+        if monotonic {
+            return true;
+        }
+
+        max_removals > 0
+            && (0..report.len()).any(|i| {
+                let mut removed = report.to_vec();
+                removed.remove(i);
+                is_safe_reference(&removed, max_removals - 1)
+            })
+    }
+
+    #[test]
+    fn dampener_differential() {
+        let mut random = Random::new(0x0202);
+
+        for _ in 0..2000 {
+            let len = random.below(9) as usize;
+            let mut report = vec![random.below(10) as i32];
+
+            for _ in 1..len {
+                let step = random.below(9) as i32 - 4;
+                report.push(report.last().unwrap() + step);
+            }
+
+            for k in 0..=3 {
+                let dampener = Dampener::new(k, 1..=3);
+                assert_eq!(
+                    dampener.is_safe(&report),
+                    is_safe_reference(&report, k),
+                    "{report:?} with k = {k}"
+                );
+
+                // whatever the diagnosis suggests removing has to actually work
+                if let Some(fix) = dampener.diagnose(&report).fix {
+                    let rest: Vec<_> = (0..report.len())
+                        .filter(|i| !fix.contains(i))
+                        .map(|i| report[i])
+                        .collect();
+
+                    assert!(fix.len() <= k, "{report:?} with k = {k}");
+                    assert!(is_safe_reference(&rest, 0), "{report:?} with k = {k}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    #[ignore]
+    fn dampener_benchmark() {
+        for levels in [10_000, 100_000, 1_000_000] {
+            // a long increasing run with a bad level every so often
+            let report: Vec<i32> = (0..levels as i32)
+                .map(|i| if i % 1000 == 500 { 0 } else { i })
+                .collect();
+
+            for k in [0, 1, 10] {
+                let dampener = Dampener::new(k, 1..=3);
+                let (safe, elapsed) = time(|| dampener.is_safe(&report));
+
+                assert_eq!(safe, levels / 1000 <= k);
+                println!("{levels:>9} levels, k = {k:>2}: {elapsed:?}");
+            }
+        }
+    }
 }
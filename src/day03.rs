@@ -1,26 +1,303 @@
 use crate::prelude::*;
-use std::str::Chars;
+use std::io::ErrorKind;
+use std::ops::Range;
 
-#[derive(Clone, Copy)]
-struct Mul {
-    left: u32,
-    right: u32,
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Mul {
+    pub left: u32,
+    pub right: u32,
 }
 
 impl Mul {
-    fn compute(self) -> u32 {
-        self.left * self.right
+    fn compute(self) -> Result<u64> {
+        let product = self
+            .left
+            .checked_mul(self.right)
+            .ok_or("multiplication overflows")?;
+
+        Ok(product as u64)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    Mul(Mul),
+    Do,
+    Dont,
+}
+
+/// An instruction found in the input, along with where it was found.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Instruction {
+    pub kind: Kind,
+    /// Byte offsets of the whole instruction in the input.
+    pub span: Range<usize>,
+}
+
+const KEYWORDS: [&[u8]; 3] = [b"mul(", b"do()", b"don't()"];
+
+/// Operands of a `mul` have one to three digits.
+const MAX_DIGITS: usize = 3;
+
+#[derive(Clone, Copy)]
+enum State {
+    Idle,
+    /// The first `len` bytes of `keyword` have been seen.
+    Keyword {
+        start: usize,
+        keyword: &'static [u8],
+        len: usize,
+    },
+    /// Inside the parentheses of a `mul`; `left` is set once the comma has been seen.
+    Operands {
+        start: usize,
+        left: Option<u32>,
+        value: u32,
+        digits: usize,
+    },
+}
+
+/// Recognizes instructions in corrupted memory, one byte at a time.
+///
+/// None of the keywords contain the first letter of a keyword anywhere but at their start, so
+/// whenever a byte doesn't fit the instruction being read, reading can start over at that byte.
+struct Lexer {
+    state: State,
+    offset: usize,
+}
+
+impl Lexer {
+    fn new() -> Self {
+        Self {
+            state: State::Idle,
+            offset: 0,
+        }
+    }
+
+    fn push(&mut self, byte: u8) -> Option<Instruction> {
+        let offset = self.offset;
+        self.offset += 1;
+
+        let (state, instruction) = Self::step(self.state, byte, offset);
+        self.state = state;
+
+        instruction
+    }
+
+    fn step(state: State, byte: u8, offset: usize) -> (State, Option<Instruction>) {
+        let emit = |start: usize, kind| {
+            let instruction = Instruction {
+                kind,
+                span: start..offset + 1,
+            };
+
+            (State::Idle, Some(instruction))
+        };
+
+        match state {
+            State::Idle => match KEYWORDS.iter().find(|keyword| keyword[0] == byte) {
+                Some(keyword) => {
+                    let state = State::Keyword {
+                        start: offset,
+                        keyword,
+                        len: 1,
+                    };
+
+                    (state, None)
+                }
+
+                None => (State::Idle, None),
+            },
+
+            State::Keyword {
+                start,
+                keyword,
+                len,
+            } => {
+                // keywords can share a prefix, so look for any that continues with this byte
+                let next = KEYWORDS.iter().find(|candidate| {
+                    candidate.len() > len
+                        && candidate[..len] == keyword[..len]
+                        && candidate[len] == byte
+                });
+
+                match next {
+                    None => Self::step(State::Idle, byte, offset),
+
+                    Some(&keyword) if len + 1 < keyword.len() => {
+                        let state = State::Keyword {
+                            start,
+                            keyword,
+                            len: len + 1,
+                        };
+
+                        (state, None)
+                    }
+
+                    Some(&b"mul(") => {
+                        let state = State::Operands {
+                            start,
+                            left: None,
+                            value: 0,
+                            digits: 0,
+                        };
+
+                        (state, None)
+                    }
+
+                    Some(&b"do()") => emit(start, Kind::Do),
+
+                    Some(_) => emit(start, Kind::Dont),
+                }
+            }
+
+            State::Operands {
+                start,
+                left,
+                value,
+                digits,
+            } => match (byte, left) {
+                (b'0'..=b'9', _) if digits < MAX_DIGITS => {
+                    let value = value
+                        .checked_mul(10)
+                        .and_then(|value| value.checked_add((byte - b'0') as u32));
+
+                    match value {
+                        Some(value) => {
+                            let state = State::Operands {
+                                start,
+                                left,
+                                value,
+                                digits: digits + 1,
+                            };
+
+                            (state, None)
+                        }
+
+                        None => Self::step(State::Idle, byte, offset),
+                    }
+                }
+
+                (b',', None) if digits > 0 => {
+                    let state = State::Operands {
+                        start,
+                        left: Some(value),
+                        value: 0,
+                        digits: 0,
+                    };
+
+                    (state, None)
+                }
+
+                (b')', Some(left)) if digits > 0 => {
+                    emit(start, Kind::Mul(Mul { left, right: value }))
+                }
+
+                _ => Self::step(State::Idle, byte, offset),
+            },
+        }
+    }
+}
+
+/// The instructions in a stream of corrupted memory, read a buffer at a time. The lexer keeps its
+/// state between buffers, so instructions straddling a buffer boundary are still recognized.
+pub struct Instructions<R> {
+    reader: R,
+    lexer: Lexer,
+}
+
+impl<R: BufRead> Instructions<R> {
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            lexer: Lexer::new(),
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for Instructions<R> {
+    type Item = Result<Instruction>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let buffer = match self.reader.fill_buf() {
+                Ok(buffer) => buffer,
+                Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(e) => return Some(Err(e.into())),
+            };
+
+            if buffer.is_empty() {
+                return None;
+            }
+
+            let mut used = 0;
+            let mut instruction = None;
+
+            for &byte in buffer {
+                used += 1;
+                instruction = self.lexer.push(byte);
+
+                if instruction.is_some() {
+                    break;
+                }
+            }
+
+            self.reader.consume(used);
+
+            if let Some(instruction) = instruction {
+                return Some(Ok(instruction));
+            }
+        }
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Totals {
+    /// Sum of every multiplication.
+    all: u64,
+    /// Sum of the multiplications that weren't disabled by a `don't()`.
+    enabled: u64,
+}
+
+/// Runs the instructions, keeping track of whether multiplications are currently enabled. They
+/// can come straight from [`Instructions`], so a stream can be run without storing it.
+fn interpret<I>(instructions: I) -> Result<Totals>
+where
+    I: Iterator<Item = Result<Instruction>>,
+{
+    let mut totals = Totals::default();
+    let mut enabled = true;
+
+    for instruction in instructions {
+        match instruction?.kind {
+            Kind::Mul(mul) => {
+                let product = mul.compute()?;
+
+                totals.all = totals.all.checked_add(product).ok_or("sum overflows")?;
+
+                if enabled {
+                    totals.enabled = totals.enabled.checked_add(product).ok_or("sum overflows")?;
+                }
+            }
+
+            Kind::Do => enabled = true,
+
+            Kind::Dont => enabled = false,
+        }
     }
+
+    Ok(totals)
 }
 
 pub struct A;
 
 impl Puzzle for A {
-    type Input = String;
-    type Output = u32;
+    type Input = Vec<Instruction>;
+    type Output = u64;
 
     fn example_input() -> Self::Input {
-        "xmul(2,4)%&mul[3,7]!@^do_not_mul(5,5)+mul(32,64]then(mul(11,8)mul(8,5))".to_owned()
+        let input_str = "xmul(2,4)%&mul[3,7]!@^do_not_mul(5,5)+mul(32,64]then(mul(11,8)mul(8,5))";
+        Self::parse_input(input_str.as_bytes()).unwrap()
     }
 
     fn example_output() -> Self::Output {
@@ -31,31 +308,28 @@ impl Puzzle for A {
         "inputs/day03/input"
     }
 
-    fn parse_input<B>(mut reader: B) -> Result<Self::Input>
+    fn parse_input<B>(reader: B) -> Result<Self::Input>
     where
         B: BufRead,
     {
-        let mut out = String::new();
-        reader.read_to_string(&mut out)?;
-        Ok(out)
+        // the input can be huge, so lex it as it's read and only keep the instructions
+        Instructions::new(reader).collect()
     }
 
-    fn solve(&mut self, input: Self::Input) -> Result<Self::Output> {
-        let parser = Parser::new(input.chars());
-        let out = parser.map(|instruction| instruction.compute()).sum();
-
-        Ok(out)
+    fn solve(&mut self, instructions: Self::Input) -> Result<Self::Output> {
+        Ok(interpret(instructions.into_iter().map(Ok))?.all)
     }
 }
 
 pub struct B;
 
 impl Puzzle for B {
-    type Input = String;
-    type Output = u32;
+    type Input = Vec<Instruction>;
+    type Output = u64;
 
     fn example_input() -> Self::Input {
-        "xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))".into()
+        let input_str = "xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))";
+        Self::parse_input(input_str.as_bytes()).unwrap()
     }
 
     fn example_output() -> Self::Output {
@@ -73,173 +347,218 @@ impl Puzzle for B {
         A::parse_input(reader)
     }
 
-    fn solve(&mut self, input: Self::Input) -> Result<Self::Output> {
-        let mut out = 0;
-        let mut split = input.split("don't()");
-
-        // gather all until first "don't()" instruction
-        if let Some(substring) = split.next() {
-            let parser = Parser::new(substring.chars());
-
-            for mul in parser {
-                out += mul.compute();
-            }
-        }
-
-        for substring in split {
-            for subsubstring in substring.split("do()").skip(1) {
-                let parser = Parser::new(subsubstring.chars());
+    fn solve(&mut self, instructions: Self::Input) -> Result<Self::Output> {
+        Ok(interpret(instructions.into_iter().map(Ok))?.enabled)
+    }
+}
 
-                for mul in parser {
-                    out += mul.compute();
-                }
-            }
-        }
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::Random;
+    use std::io::{BufReader, Read};
 
-        Ok(out)
+    #[test]
+    fn a() -> Result<()> {
+        let mut a = A;
+        a.test_example()
     }
-}
 
-#[derive(Clone, Copy, PartialEq, Eq)]
-enum Token {
-    M,
-    U,
-    L,
-    OpenParen,
-    ClosingParen,
-    Comma,
-    Digit(u32),
-}
+    const EXAMPLE: &str =
+        "xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))";
 
-impl Token {
-    fn follows(self, other: Self) -> bool {
-        use Token::*;
-
-        matches!(
-            (other, self),
-            (M, U)
-                | (U, L)
-                | (L, OpenParen)
-                | (OpenParen, Digit(_))
-                | (Digit(_), Digit(_))
-                | (Digit(_), Comma)
-                | (Comma, Digit(_))
-                | (Digit(_), ClosingParen)
-        )
+    fn lex(input: &str) -> Vec<Instruction> {
+        Instructions::new(input.as_bytes())
+            .collect::<Result<_>>()
+            .unwrap()
     }
-}
 
-impl TryFrom<char> for Token {
-    type Error = &'static str;
-
-    fn try_from(value: char) -> std::result::Result<Self, Self::Error> {
-        match value {
-            'm' => Ok(Self::M),
-            'u' => Ok(Self::U),
-            'l' => Ok(Self::L),
-            '(' => Ok(Self::OpenParen),
-            ')' => Ok(Self::ClosingParen),
-            ',' => Ok(Self::Comma),
-            other => match other.to_digit(10) {
-                Some(digit) => Ok(Self::Digit(digit)),
-                None => Err("unrecognized token"),
+    #[test]
+    fn instructions() -> Result<()> {
+        let input = EXAMPLE;
+        let instructions = lex(input);
+
+        let mul = |left, right, span| Instruction {
+            kind: Kind::Mul(Mul { left, right }),
+            span,
+        };
+
+        let expected = vec![
+            mul(2, 4, 1..9),
+            Instruction {
+                kind: Kind::Dont,
+                span: 20..27,
+            },
+            mul(5, 5, 28..36),
+            mul(11, 8, 48..57),
+            Instruction {
+                kind: Kind::Do,
+                span: 59..63,
             },
+            mul(8, 5, 64..72),
+        ];
+
+        assert_eq!(instructions, expected);
+
+        for instruction in instructions {
+            assert!(input[instruction.span].ends_with(')'));
         }
+
+        assert_eq!(
+            interpret(Instructions::new(input.as_bytes()))?,
+            Totals {
+                all: 161,
+                enabled: 48
+            }
+        );
+
+        Ok(())
     }
-}
 
-struct Parser<'a> {
-    input: Chars<'a>,
-    left: u32,
-    right: u32,
-    comma_seen: bool,
-    last_token: Token,
-}
+    #[test]
+    fn operand_digits() {
+        let mul = |left, right| Mul { left, right };
 
-impl<'a> Parser<'a> {
-    fn new(input: Chars<'a>) -> Self {
-        Self {
-            input,
-            left: 0,
-            right: 0,
-            comma_seen: false,
-            last_token: Token::ClosingParen,
-        }
+        let found: Vec<_> = muls("mul(1234,5)mul(123,4567)mul(,1)mul(1,)mul(999,999)").collect();
+        assert_eq!(found, vec![(38, mul(999, 999))]);
+
+        // long digit runs used to overflow
+        let long = format!("mul({},2)mul(12,3)", "9".repeat(20));
+        let found: Vec<_> = muls(&long).collect();
+        assert_eq!(found, vec![(27, mul(12, 3))]);
     }
 
-    fn reset(&mut self) {
-        self.left = 0;
-        self.right = 0;
-        self.comma_seen = false;
-        self.last_token = Token::ClosingParen;
+    /// Every valid `mul` in `input`, with the byte offset it starts at.
+    fn muls(input: &str) -> impl Iterator<Item = (usize, Mul)> + '_ {
+        lex(input)
+            .into_iter()
+            .filter_map(|instruction| match instruction.kind {
+                Kind::Mul(mul) => Some((instruction.span.start, mul)),
+                _ => None,
+            })
     }
-}
 
-impl Iterator for Parser<'_> {
-    type Item = Mul;
+    /// Checks every offset for `mul(` followed by 1-3 digits, a comma, 1-3 digits and `)`.
+    fn muls_reference(input: &str) -> Vec<(usize, Mul)> {
+        let bytes = input.as_bytes();
 
-    fn next(&mut self) -> Option<Self::Item> {
-        loop {
-            let c = self.input.next()?;
+        let number = |at: usize| {
+            let digits = bytes[at..]
+                .iter()
+                .take_while(|b| b.is_ascii_digit())
+                .count();
 
-            let token = match Token::try_from(c) {
-                Ok(token) => token,
-                Err(_) => {
-                    self.reset();
-                    continue;
-                }
+            (1..=3)
+                .contains(&digits)
+                .then(|| (input[at..at + digits].parse().unwrap(), at + digits))
+        };
+
+        let mut found = Vec::new();
+
+        for start in 0..bytes.len() {
+            if !bytes[start..].starts_with(b"mul(") {
+                continue;
+            }
+
+            let Some((left, at)) = number(start + 4) else {
+                continue;
             };
 
-            if token == Token::M {
-                self.reset();
-                self.last_token = token;
+            if bytes.get(at) != Some(&b',') {
                 continue;
             }
 
-            if !token.follows(self.last_token) {
-                self.reset();
+            let Some((right, at)) = number(at + 1) else {
                 continue;
+            };
+
+            if bytes.get(at) == Some(&b')') {
+                found.push((start, Mul { left, right }));
             }
+        }
 
-            match token {
-                Token::ClosingParen => {
-                    let mul = Mul {
-                        left: self.left,
-                        right: self.right,
-                    };
+        found
+    }
 
-                    self.reset();
-                    return Some(mul);
-                }
+    #[test]
+    fn muls_differential() {
+        let mut random = Random::new(0x0303);
+
+        let pieces = [
+            "mul(", "mul", "(", ")", ",", "1", "23", "4567", "do()", "don't()", "x",
+        ];
+
+        for _ in 0..500 {
+            let input: String = (0..40)
+                .map(|_| pieces[random.below(pieces.len() as u64) as usize])
+                .collect();
+
+            assert_eq!(
+                muls(&input).collect::<Vec<_>>(),
+                muls_reference(&input),
+                "{input}"
+            );
+        }
+    }
 
-                Token::Comma => self.comma_seen = true,
+    #[test]
+    fn buffer_boundaries() {
+        let expected = lex(EXAMPLE);
 
-                Token::Digit(digit) => {
-                    if self.comma_seen {
-                        self.right *= 10;
-                        self.right += digit;
-                    } else {
-                        self.left *= 10;
-                        self.left += digit;
-                    }
-                }
+        // every possible split of instructions across buffers
+        for capacity in 1..=EXAMPLE.len() {
+            let reader = BufReader::with_capacity(capacity, EXAMPLE.as_bytes());
+            let instructions: Vec<_> = Instructions::new(reader).collect::<Result<_>>().unwrap();
 
-                _ => {}
+            assert_eq!(instructions, expected, "capacity {capacity}");
+        }
+    }
+
+    /// Produces `chunk` over and over without ever holding more than one copy of it.
+    struct Repeat {
+        chunk: &'static [u8],
+        position: usize,
+        remaining: usize,
+    }
+
+    impl Read for Repeat {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            if self.remaining == 0 {
+                return Ok(0);
             }
 
-            self.last_token = token;
+            let n = buf.len().min(self.chunk.len() - self.position);
+            buf[..n].copy_from_slice(&self.chunk[self.position..self.position + n]);
+
+            self.position += n;
+
+            if self.position == self.chunk.len() {
+                self.position = 0;
+                self.remaining -= 1;
+            }
+
+            Ok(n)
         }
     }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
 
     #[test]
-    fn a() -> Result<()> {
-        let mut a = A;
-        a.test_example()
+    fn stream() -> Result<()> {
+        // 2 * 4 + 999 * 999 + 3 * 3 per chunk, with the middle one disabled
+        let chunk = b"xmul(2,4)don't()mul(999,999)do()mul(3,3)";
+        let repeats = 100_000;
+
+        let reader = Repeat {
+            chunk,
+            position: 0,
+            remaining: repeats,
+        };
+
+        let totals = interpret(Instructions::new(BufReader::with_capacity(4096, reader)))?;
+
+        assert_eq!(totals.all, repeats as u64 * (8 + 998001 + 9));
+        assert_eq!(totals.enabled, repeats as u64 * (8 + 9));
+
+        Ok(())
     }
 
     #[test]
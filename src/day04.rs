@@ -1,101 +1,167 @@
 use crate::prelude::*;
 
-pub struct Input {
-    lines: Vec<Vec<char>>,
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    Up,
+    UpRight,
+    Right,
+    DownRight,
+    Down,
+    DownLeft,
+    Left,
+    UpLeft,
 }
 
-impl Input {
-    fn find_up(&self, x: usize, y: usize) -> bool {
-        if y < 3 {
-            return false;
+impl Direction {
+    pub const ALL: [Self; 8] = [
+        Self::Up,
+        Self::UpRight,
+        Self::Right,
+        Self::DownRight,
+        Self::Down,
+        Self::DownLeft,
+        Self::Left,
+        Self::UpLeft,
+    ];
+
+    /// How far a single step in this direction moves along x and y.
+    fn offset(self) -> (isize, isize) {
+        match self {
+            Self::Up => (0, -1),
+            Self::UpRight => (1, -1),
+            Self::Right => (1, 0),
+            Self::DownRight => (1, 1),
+            Self::Down => (0, 1),
+            Self::DownLeft => (-1, 1),
+            Self::Left => (-1, 0),
+            Self::UpLeft => (-1, -1),
         }
-
-        self.lines[y - 1][x] == 'M' && self.lines[y - 2][x] == 'A' && self.lines[y - 3][x] == 'S'
     }
+}
 
-    fn find_down(&self, x: usize, y: usize) -> bool {
-        if y + 4 > self.lines.len() {
-            return false;
-        }
+/// What to look for in the grid, and how.
+pub struct Search {
+    pub word: Vec<char>,
+    pub directions: Vec<Direction>,
+    /// Lets words continue on the opposite edge of the grid when they run off one edge.
+    pub wrap: bool,
+    pub case_insensitive: bool,
+}
 
-        self.lines[y + 1][x] == 'M' && self.lines[y + 2][x] == 'A' && self.lines[y + 3][x] == 'S'
+impl Search {
+    /// Looks for `word` in every direction, without wrapping around and matching case.
+    pub fn new(word: &str) -> Self {
+        Self {
+            word: word.chars().collect(),
+            directions: Direction::ALL.to_vec(),
+            wrap: false,
+            case_insensitive: false,
+        }
     }
 
-    fn find_up_left(&self, x: usize, y: usize) -> bool {
-        if x < 3 || y < 3 {
-            return false;
+    fn matches(&self, a: char, b: char) -> bool {
+        if self.case_insensitive {
+            a.to_lowercase().eq(b.to_lowercase())
+        } else {
+            a == b
         }
-
-        self.lines[y - 1][x - 1] == 'M'
-            && self.lines[y - 2][x - 2] == 'A'
-            && self.lines[y - 3][x - 3] == 'S'
     }
+}
 
-    fn find_up_right(&self, x: usize, y: usize) -> bool {
-        if x + 4 > self.lines[0].len() || y < 3 {
-            return false;
-        }
+/// Where a word was found: the position of its first letter and the way the rest follows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Match {
+    pub x: usize,
+    pub y: usize,
+    pub direction: Direction,
+}
 
-        self.lines[y - 1][x + 1] == 'M'
-            && self.lines[y - 2][x + 2] == 'A'
-            && self.lines[y - 3][x + 3] == 'S'
-    }
+pub struct Input {
+    lines: Vec<Vec<char>>,
+}
 
-    fn find_down_left(&self, x: usize, y: usize) -> bool {
-        if x < 3 || y + 4 > self.lines.len() {
-            return false;
-        }
+impl Input {
+    fn get(&self, x: isize, y: isize, wrap: bool) -> Option<char> {
+        let (x, y) = if wrap {
+            let height = self.lines.len() as isize;
+            if height == 0 {
+                return None;
+            }
 
-        self.lines[y + 1][x - 1] == 'M'
-            && self.lines[y + 2][x - 2] == 'A'
-            && self.lines[y + 3][x - 3] == 'S'
-    }
+            let y = y.rem_euclid(height);
+            let width = self.lines[y as usize].len() as isize;
+            if width == 0 {
+                return None;
+            }
 
-    fn find_down_right(&self, x: usize, y: usize) -> bool {
-        if x + 4 > self.lines[0].len() || y + 4 > self.lines.len() {
-            return false;
-        }
+            (x.rem_euclid(width), y)
+        } else {
+            (x, y)
+        };
 
-        self.lines[y + 1][x + 1] == 'M'
-            && self.lines[y + 2][x + 2] == 'A'
-            && self.lines[y + 3][x + 3] == 'S'
+        let line = self.lines.get(usize::try_from(y).ok()?)?;
+        line.get(usize::try_from(x).ok()?).copied()
     }
 
-    fn count_xmas(&self) -> u32 {
-        let mut count = 0;
+    fn matches_at(&self, search: &Search, x: usize, y: usize, direction: Direction) -> bool {
+        let (dx, dy) = direction.offset();
 
-        for (y, line) in self.lines.iter().enumerate() {
-            for (x, &c) in line.iter().enumerate() {
-                if c == 'X' {
-                    if x >= 3 && line[x - 3..x] == ['S', 'A', 'M'] {
-                        count += 1;
-                    }
+        search.word.iter().enumerate().all(|(i, &c)| {
+            let i = i as isize;
 
-                    if x < line.len() - 3 && line[x + 1..x + 4] == ['M', 'A', 'S'] {
-                        count += 1;
-                    }
+            match self.get(x as isize + dx * i, y as isize + dy * i, search.wrap) {
+                Some(found) => search.matches(found, c),
+                None => false,
+            }
+        })
+    }
 
-                    if self.find_up(x, y) {
-                        count += 1;
-                    }
+    /// Every occurrence of the search word, by where it starts and which way it goes.
+    pub fn find(&self, search: &Search) -> Vec<Match> {
+        let mut matches = Vec::new();
 
-                    if self.find_down(x, y) {
-                        count += 1;
-                    }
+        if search.word.is_empty() {
+            return matches;
+        }
 
-                    if self.find_up_left(x, y) {
-                        count += 1;
+        for (y, line) in self.lines.iter().enumerate() {
+            for x in 0..line.len() {
+                for &direction in &search.directions {
+                    if self.matches_at(search, x, y, direction) {
+                        matches.push(Match { x, y, direction });
                     }
+                }
+            }
+        }
 
-                    if self.find_up_right(x, y) {
-                        count += 1;
-                    }
+        matches
+    }
 
-                    if self.find_down_left(x, y) {
-                        count += 1;
-                    }
+    /// Whether `stencil` matches with its top left corner at `x`, `y`. Every cell has to be on
+    /// the grid, wildcards too, so the count doesn't depend on which way the stencil is turned.
+    fn stencil_matches_at(&self, stencil: &Stencil, x: usize, y: usize) -> bool {
+        stencil.cells.iter().enumerate().all(|(dy, row)| {
+            row.iter().enumerate().all(|(dx, cell)| {
+                match (
+                    cell,
+                    self.lines.get(y + dy).and_then(|line| line.get(x + dx)),
+                ) {
+                    (_, None) => false,
+                    (Some(c), Some(found)) => c == found,
+                    (None, Some(_)) => true,
+                }
+            })
+        })
+    }
 
-                    if self.find_down_right(x, y) {
+    /// Number of places `stencil` appears in the grid, in any of its orientations.
+    pub fn count_stencil(&self, stencil: &Stencil) -> u32 {
+        let mut count = 0;
+
+        for variant in stencil.variants() {
+            for (y, line) in self.lines.iter().enumerate() {
+                for x in 0..line.len() {
+                    if self.stencil_matches_at(&variant, x, y) {
                         count += 1;
                     }
                 }
@@ -104,51 +170,76 @@ impl Input {
 
         count
     }
+}
 
-    fn count_x_mas(&self) -> u32 {
-        let mut count = 0;
+/// A small grid of characters to look for, where `None` matches anything.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Stencil {
+    cells: Vec<Vec<Option<char>>>,
+}
 
-        for (y, line) in self.lines.iter().enumerate() {
-            for (x, &top_left) in line.iter().enumerate() {
-                let top_right = match line.get(x + 2) {
-                    Some(&top_right) => top_right,
-                    None => continue,
-                };
-
-                let bottom_left = match self.lines.get(y + 2).and_then(|line| line.get(x)) {
-                    Some(&bottom_left) => bottom_left,
-                    None => continue,
-                };
-
-                let bottom_right = match self.lines.get(y + 2).and_then(|line| line.get(x + 2)) {
-                    Some(&bottom_right) => bottom_right,
-                    None => continue,
-                };
-
-                match self.lines.get(y + 1).and_then(|line| line.get(x + 1)) {
-                    Some(&center) => {
-                        if center != 'A' {
-                            continue;
-                        }
-                    }
-                    None => continue,
-                }
+impl Stencil {
+    /// Builds a stencil from rows of text, with `.` as the wildcard. Short rows are padded with
+    /// wildcards to make the stencil rectangular.
+    pub fn new(rows: &[&str]) -> Self {
+        let mut stencil = Self {
+            cells: rows
+                .iter()
+                .map(|row| row.chars().map(|c| (c != '.').then_some(c)).collect())
+                .collect(),
+        };
+
+        let width = stencil.width();
+        for row in &mut stencil.cells {
+            row.resize(width, None);
+        }
 
-                match (top_left, bottom_right) {
-                    ('M', 'S') | ('S', 'M') => {}
-                    _ => continue,
-                }
+        stencil
+    }
 
-                match (top_right, bottom_left) {
-                    ('M', 'S') | ('S', 'M') => {}
-                    _ => continue,
-                }
+    fn width(&self) -> usize {
+        self.cells.iter().map(Vec::len).max().unwrap_or(0)
+    }
+
+    /// Turned a quarter clockwise.
+    fn rotate(&self) -> Self {
+        let height = self.cells.len();
+
+        let cells = (0..self.width())
+            .map(|x| (0..height).rev().map(|y| self.cells[y][x]).collect())
+            .collect();
 
-                count += 1;
+        Self { cells }
+    }
+
+    /// Mirrored left to right.
+    fn reflect(&self) -> Self {
+        let cells = self
+            .cells
+            .iter()
+            .map(|row| row.iter().rev().copied().collect())
+            .collect();
+
+        Self { cells }
+    }
+
+    /// Every distinct rotation and reflection of this stencil, so symmetric stencils aren't
+    /// counted more than once per place.
+    fn variants(&self) -> Vec<Self> {
+        let mut variants: Vec<Self> = Vec::new();
+        let mut current = self.clone();
+
+        for _ in 0..4 {
+            for variant in [current.clone(), current.reflect()] {
+                if !variants.contains(&variant) {
+                    variants.push(variant);
+                }
             }
+
+            current = current.rotate();
         }
 
-        count
+        variants
     }
 }
 
@@ -190,14 +281,18 @@ impl Puzzle for A {
         let mut lines = Vec::new();
 
         for line in reader.lines() {
-            lines.push(line?.chars().collect());
+            let line = line?;
+
+            if !line.is_empty() {
+                lines.push(line.chars().collect());
+            }
         }
 
         Ok(Input { lines })
     }
 
     fn solve(&mut self, input: Self::Input) -> Result<Self::Output> {
-        Ok(input.count_xmas())
+        Ok(input.find(&Search::new("XMAS")).len() as u32)
     }
 }
 
@@ -227,7 +322,8 @@ impl Puzzle for B {
     }
 
     fn solve(&mut self, input: Self::Input) -> Result<Self::Output> {
-        Ok(input.count_x_mas())
+        let x_mas = Stencil::new(&["M.S", ".A.", "M.S"]);
+        Ok(input.count_stencil(&x_mas))
     }
 }
 
@@ -241,6 +337,127 @@ mod tests {
         a.test_example()
     }
 
+    #[test]
+    fn search() {
+        let input = Input {
+            lines: vec!["abc".chars().collect(), "Def".chars().collect()],
+        };
+
+        let found = |x, y, direction| Match { x, y, direction };
+        let rightwards = |word| Search {
+            directions: vec![Direction::Right],
+            ..Search::new(word)
+        };
+
+        assert_eq!(
+            input.find(&Search::new("bc")),
+            vec![found(1, 0, Direction::Right)]
+        );
+
+        assert_eq!(
+            input.find(&Search {
+                case_insensitive: true,
+                ..Search::new("ad")
+            }),
+            vec![found(0, 0, Direction::Down)]
+        );
+
+        assert_eq!(input.find(&rightwards("ca")), vec![]);
+
+        assert_eq!(
+            input.find(&Search {
+                wrap: true,
+                ..rightwards("ca")
+            }),
+            vec![found(2, 0, Direction::Right)]
+        );
+
+        // with only two rows, going up and going down wrap around to the same place
+        assert_eq!(
+            input.find(&Search {
+                wrap: true,
+                ..Search::new("fa")
+            }),
+            vec![
+                found(2, 1, Direction::UpRight),
+                found(2, 1, Direction::DownRight)
+            ]
+        );
+    }
+
+    #[test]
+    fn blank_lines() -> Result<()> {
+        let input = A::parse_input("ab\nba\n\n".as_bytes())?;
+        assert_eq!(input.lines.len(), 2);
+
+        let search = Search {
+            wrap: true,
+            ..Search::new("ab")
+        };
+        assert_eq!(input.find(&search).len(), 8);
+
+        let empty = Input {
+            lines: vec![vec![]],
+        };
+        assert_eq!(empty.get(1, 0, true), None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn stencils() {
+        let input = A::example_input();
+
+        // a straight line can only be turned four ways
+        let orthogonal = Search {
+            directions: vec![
+                Direction::Up,
+                Direction::Right,
+                Direction::Down,
+                Direction::Left,
+            ],
+            ..Search::new("XMAS")
+        };
+
+        let line = Stencil::new(&["XMAS"]);
+        assert_eq!(line.variants().len(), 4);
+        assert_eq!(
+            input.count_stencil(&line),
+            input.find(&orthogonal).len() as u32
+        );
+
+        // fully symmetric stencils only count once per place
+        let a = Stencil::new(&["A"]);
+        assert_eq!(a.variants().len(), 1);
+
+        let count = input.lines.iter().flatten().filter(|&&c| c == 'A').count();
+        assert_eq!(input.count_stencil(&a), count as u32);
+
+        let x_mas = Stencil::new(&["M.S", ".A.", "M.S"]);
+        assert_eq!(x_mas.variants().len(), 4);
+
+        let corner = Stencil::new(&["XM", "A."]);
+        assert_eq!(corner.variants().len(), 8);
+        assert_eq!(corner.rotate().rotate().rotate().rotate(), corner);
+
+        // wildcards have to be on the grid too, whichever way the stencil is turned
+        let single = Input {
+            lines: vec![vec!['A']],
+        };
+        assert_eq!(single.count_stencil(&Stencil::new(&["A."])), 0);
+
+        // short rows are padded, so they don't turn into extra variants
+        let ragged = Stencil::new(&["A.", "A"]);
+        let padded = Stencil::new(&["A.", "A."]);
+        assert_eq!(ragged, padded);
+        assert_eq!(ragged.variants().len(), 4);
+
+        let column = Input {
+            lines: vec![vec!['A', 'B'], vec!['A', 'B']],
+        };
+        assert_eq!(column.count_stencil(&ragged), 1);
+    }
+
     #[test]
     fn b() -> Result<()> {
         let mut b = B;
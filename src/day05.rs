@@ -1,8 +1,9 @@
 use crate::prelude::*;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt;
 use std::str::FromStr;
 
-#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct Rule {
     earlier: u32,
     later: u32,
@@ -27,27 +28,161 @@ impl FromStr for Rule {
     }
 }
 
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}|{}", self.earlier, self.later)
+    }
+}
+
+/// The ordering rules, indexed by the page that has to come first.
+#[derive(Default)]
+pub struct Rules {
+    successors: HashMap<u32, HashSet<u32>>,
+}
+
+impl Rules {
+    fn insert(&mut self, rule: Rule) {
+        self.successors
+            .entry(rule.earlier)
+            .or_default()
+            .insert(rule.later);
+    }
+
+    fn requires(&self, earlier: u32, later: u32) -> bool {
+        self.successors
+            .get(&earlier)
+            .is_some_and(|successors| successors.contains(&later))
+    }
+}
+
+impl FromIterator<Rule> for Rules {
+    fn from_iter<I: IntoIterator<Item = Rule>>(rules: I) -> Self {
+        let mut index = Self::default();
+
+        for rule in rules {
+            index.insert(rule);
+        }
+
+        index
+    }
+}
+
+/// Pages whose ordering rules contradict each other, each one required to come before the next
+/// and the last one before the first again.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Cycle {
+    pages: Vec<u32>,
+}
+
+impl Cycle {
+    /// Finds a cycle in the graph given by `successors`, with a depth-first search that stops at
+    /// the first edge leading back to a page still on the stack.
+    fn find(successors: &HashMap<u32, Vec<u32>>) -> Option<Self> {
+        // rules come out of a hash set, so sort everything to always report the same cycle
+        let mut successors = successors.clone();
+        for later in successors.values_mut() {
+            later.sort_unstable();
+        }
+
+        let mut starts: Vec<u32> = successors.keys().copied().collect();
+        starts.sort_unstable();
+
+        let mut done = HashSet::new();
+
+        for start in starts {
+            if done.contains(&start) {
+                continue;
+            }
+
+            // pages on the current path, along with how many of their successors were visited
+            let mut path = vec![(start, 0)];
+            let mut on_path = HashSet::from([start]);
+
+            while let Some((page, next)) = path.last_mut() {
+                let page = *page;
+                let Some(&later) = successors.get(&page).and_then(|s| s.get(*next)) else {
+                    on_path.remove(&page);
+                    done.insert(page);
+                    path.pop();
+                    continue;
+                };
+
+                *next += 1;
+
+                if on_path.contains(&later) {
+                    let from = path.iter().position(|&(p, _)| p == later).unwrap();
+                    let mut pages: Vec<u32> = path[from..].iter().map(|&(p, _)| p).collect();
+                    pages.push(later);
+
+                    return Some(Self { pages });
+                }
+
+                if !done.contains(&later) {
+                    on_path.insert(later);
+                    path.push((later, 0));
+                }
+            }
+        }
+
+        None
+    }
+}
+
+impl fmt::Display for Cycle {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "ordering rules contain a cycle: ")?;
+
+        for (i, page) in self.pages.iter().enumerate() {
+            if i > 0 {
+                write!(f, " -> ")?;
+            }
+
+            write!(f, "{page}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl std::error::Error for Cycle {}
+
 pub struct Update {
     pages: Vec<u32>,
 }
 
 impl Update {
-    fn follows(&self, rule: &Rule) -> bool {
-        let earlier = self.pages.iter().position(|&item| item == rule.earlier);
-        let later = self.pages.iter().position(|&item| item == rule.later);
+    /// Where each page is in the update.
+    fn positions(&self) -> HashMap<u32, usize> {
+        let mut positions = HashMap::with_capacity(self.pages.len());
 
-        match (earlier, later) {
-            (Some(lower), Some(higher)) => lower < higher,
-            _ => true,
+        for (i, &page) in self.pages.iter().enumerate() {
+            positions.entry(page).or_insert(i);
         }
+
+        positions
     }
 
-    fn follows_all<'a, R>(&self, rules: R) -> bool
-    where
-        R: Iterator<Item = &'a Rule>,
-    {
-        for rule in rules {
-            if !self.follows(rule) {
+    /// Whether no page comes after one that the rules want to see after it. For each page, this
+    /// either looks up where its successors are or checks the pages before it against them,
+    /// whichever is fewer, so it's quadratic in the length of the update at worst no matter how
+    /// many rules there are.
+    fn follows_all(&self, rules: &Rules) -> bool {
+        let positions = self.positions();
+
+        for (i, page) in self.pages.iter().enumerate() {
+            let Some(later) = rules.successors.get(page) else {
+                continue;
+            };
+
+            let broken = if later.len() < i {
+                later
+                    .iter()
+                    .any(|p| positions.get(p).is_some_and(|&j| j < i))
+            } else {
+                self.pages[..i].iter().any(|p| later.contains(p))
+            };
+
+            if broken {
                 return false;
             }
         }
@@ -55,32 +190,85 @@ impl Update {
         true
     }
 
-    fn fix<'a, R>(&mut self, rules: R)
-    where
-        R: Iterator<Item = &'a Rule>,
-    {
-        for rule in rules {
-            if !self.follows(rule) {
-                let earlier = self
-                    .pages
-                    .iter()
-                    .position(|&item| item == rule.earlier)
-                    .unwrap();
+    /// Every rule the update breaks, in the order the later of the two pages appears in it.
+    fn violations(&self, rules: &Rules) -> Vec<Violation> {
+        let mut violations = Vec::new();
+
+        for (i, &page) in self.pages.iter().enumerate() {
+            for (j, &before) in self.pages[..i].iter().enumerate() {
+                if rules.requires(page, before) {
+                    violations.push(Violation {
+                        rule: Rule::new(page, before),
+                        earlier: i,
+                        later: j,
+                    });
+                }
+            }
+        }
 
-                let later = self
-                    .pages
-                    .iter()
-                    .position(|&item| item == rule.later)
-                    .unwrap();
+        violations
+    }
+
+    /// Reorders the pages so they follow every rule that applies to them, using Kahn's
+    /// algorithm on the rules restricted to this update's pages. Fails with the offending
+    /// [`Cycle`] if those rules can't all be satisfied at once.
+    fn fix(&mut self, rules: &Rules) -> Result<()> {
+        // every copy of a page would count against its successors again
+        if let Some(page) = self.repeated_page() {
+            return Err(format!("page {page} appears more than once").into());
+        }
+
+        let mut successors: HashMap<u32, Vec<u32>> = HashMap::new();
+        let mut predecessors: HashMap<u32, usize> = HashMap::new();
 
-                self.pages.swap(earlier, later);
+        for &earlier in &self.pages {
+            for &later in &self.pages {
+                if rules.requires(earlier, later) {
+                    successors.entry(earlier).or_default().push(later);
+                    *predecessors.entry(later).or_default() += 1;
+                }
             }
         }
+
+        let mut queue: VecDeque<u32> = self
+            .pages
+            .iter()
+            .copied()
+            .filter(|page| !predecessors.contains_key(page))
+            .collect();
+
+        let mut ordered = Vec::with_capacity(self.pages.len());
+
+        while let Some(page) = queue.pop_front() {
+            ordered.push(page);
+
+            for later in successors.get(&page).into_iter().flatten() {
+                let remaining = predecessors.get_mut(later).unwrap();
+                *remaining -= 1;
+
+                if *remaining == 0 {
+                    queue.push_back(*later);
+                }
+            }
+        }
+
+        if ordered.len() != self.pages.len() {
+            let cycle = Cycle::find(&successors).ok_or("ordering rules contain a cycle")?;
+            return Err(cycle.into());
+        }
+
+        self.pages = ordered;
+        Ok(())
     }
 
     fn middle_page(&self) -> u32 {
         self.pages[self.pages.len() / 2]
     }
+
+    fn repeated_page(&self) -> Option<u32> {
+        let mut seen = HashSet::new();
+        self.pages.iter().copied().find(|&page| !seen.insert(page))
+    }
 }
 
 impl FromStr for Update {
@@ -97,11 +285,125 @@ impl FromStr for Update {
     }
 }
 
+/// A rule broken by an update, with the positions its two pages have there.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Violation {
+    rule: Rule,
+    earlier: usize,
+    later: usize,
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} ({} at {}, {} at {})",
+            self.rule, self.rule.earlier, self.earlier, self.rule.later, self.later
+        )
+    }
+}
+
+/// What's wrong with an invalid update and how part B puts it right.
+pub struct Audit {
+    /// Number of the update in the input, counting from 1.
+    number: usize,
+    pages: Vec<u32>,
+    violations: Vec<Violation>,
+    fixed: Update,
+}
+
+impl Audit {
+    pub fn middle_page(&self) -> u32 {
+        self.fixed.middle_page()
+    }
+}
+
+impl fmt::Display for Audit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "update {}: {:?} breaks ", self.number, self.pages)?;
+
+        for (i, violation) in self.violations.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+
+            write!(f, "{violation}")?;
+        }
+
+        write!(
+            f,
+            ", fixed to {:?} with middle page {}",
+            self.fixed.pages,
+            self.middle_page()
+        )
+    }
+}
+
 pub struct Input {
-    rules: HashSet<Rule>,
+    rules: Rules,
     updates: Vec<Update>,
 }
 
+impl Input {
+    /// A cycle in the rules taken as a whole, ignoring which pages appear together in an update.
+    /// The puzzle input has one, so this isn't an error by itself; only a cycle within an update
+    /// is.
+    fn cycle(&self) -> Option<Cycle> {
+        let successors = self
+            .rules
+            .successors
+            .iter()
+            .map(|(&page, later)| (page, later.iter().copied().collect()))
+            .collect();
+
+        Cycle::find(&successors)
+    }
+
+    /// Audits every update that breaks a rule, fixing it the same way part B does.
+    fn audit(self) -> Result<Vec<Audit>> {
+        let mut audits = Vec::new();
+
+        for (i, update) in self.updates.into_iter().enumerate() {
+            let violations = update.violations(&self.rules);
+
+            if violations.is_empty() {
+                continue;
+            }
+
+            let pages = update.pages.clone();
+            let mut fixed = update;
+            fixed
+                .fix(&self.rules)
+                .map_err(|e| format!("update {}: {e}", i + 1))?;
+
+            audits.push(Audit {
+                number: i + 1,
+                pages,
+                violations,
+                fixed,
+            });
+        }
+
+        Ok(audits)
+    }
+}
+
+pub struct Explanation {
+    /// A cycle in the rules as a whole, if there is one.
+    pub cycle: Option<Cycle>,
+    pub audits: Vec<Audit>,
+}
+
+/// Checks the rules of the puzzle input for cycles and audits every invalid update.
+pub fn explain() -> Result<Explanation> {
+    let input = A::parse_input(A::load_input()?)?;
+
+    Ok(Explanation {
+        cycle: input.cycle(),
+        audits: input.audit()?,
+    })
+}
+
 pub struct A;
 
 impl Puzzle for A {
@@ -133,7 +435,7 @@ impl Puzzle for A {
             Rule::new(53, 13),
         ];
 
-        let rules = HashSet::from_iter(rules);
+        let rules = Rules::from_iter(rules);
 
         let updates = vec![
             Update {
@@ -171,7 +473,7 @@ impl Puzzle for A {
     where
         B: BufRead,
     {
-        let mut rules = HashSet::new();
+        let mut rules = Rules::default();
         let mut updates = Vec::new();
 
         for line in reader.lines() {
@@ -191,7 +493,7 @@ impl Puzzle for A {
         let out = input
             .updates
             .iter()
-            .filter(|update| update.follows_all(input.rules.iter()))
+            .filter(|update| update.follows_all(&input.rules))
             .map(Update::middle_page)
             .sum();
 
@@ -226,16 +528,14 @@ impl Puzzle for B {
 
     fn solve(&mut self, mut input: Self::Input) -> Result<Self::Output> {
         let mut sum = 0;
-        for update in input
-            .updates
-            .iter_mut()
-            .filter(|update| !update.follows_all(input.rules.iter()))
-        {
-            // this feels like it shouldn't work, but it does...
-            while !update.follows_all(input.rules.iter()) {
-                update.fix(input.rules.iter());
+        for (i, update) in input.updates.iter_mut().enumerate() {
+            if update.follows_all(&input.rules) {
+                continue;
             }
 
+            update
+                .fix(&input.rules)
+                .map_err(|e| format!("update {}: {e}", i + 1))?;
             sum += update.middle_page();
         }
 
@@ -246,6 +546,7 @@ impl Puzzle for B {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::testing::{time, Random};
 
     #[test]
     fn a() -> Result<()> {
@@ -253,9 +554,248 @@ mod tests {
         a.test_example()
     }
 
+    #[test]
+    fn fix() -> Result<()> {
+        let input = A::example_input();
+
+        let expected = [
+            vec![75, 47, 61, 53, 29],
+            vec![97, 61, 53, 29, 13],
+            vec![75, 29, 13],
+            vec![97, 75, 47, 61, 53],
+            vec![61, 29, 13],
+            vec![97, 75, 47, 29, 13],
+        ];
+
+        for (mut update, expected) in input.updates.into_iter().zip(expected) {
+            update.fix(&input.rules)?;
+            assert_eq!(update.pages, expected);
+            assert!(update.follows_all(&input.rules));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn repeated_pages() -> Result<()> {
+        // part A doesn't care, only fixing the order does
+        let input = "3|4\n\n4,3,3\n3,4,5\n";
+        assert_eq!(A.solve(A::parse_input(input.as_bytes())?)?, 4);
+
+        let error = B.solve(B::parse_input(input.as_bytes())?).unwrap_err();
+        assert_eq!(error.to_string(), "update 1: page 3 appears more than once");
+
+        let rules = Rules::from_iter([Rule::new(3, 4)]);
+        let mut update = Update {
+            pages: vec![4, 3, 3],
+        };
+
+        let error = update.fix(&rules).unwrap_err();
+        assert_eq!(error.to_string(), "page 3 appears more than once");
+
+        Ok(())
+    }
+
+    #[test]
+    fn audit() -> Result<()> {
+        let audits = A::example_input().audit()?;
+
+        let numbers: Vec<_> = audits.iter().map(|audit| audit.number).collect();
+        assert_eq!(numbers, vec![4, 5, 6]);
+
+        let violation = |earlier, later, positions: (usize, usize)| Violation {
+            rule: Rule::new(earlier, later),
+            earlier: positions.0,
+            later: positions.1,
+        };
+
+        assert_eq!(audits[0].violations, vec![violation(97, 75, (1, 0))]);
+        assert_eq!(audits[1].violations, vec![violation(29, 13, (2, 1))]);
+        assert_eq!(
+            audits[2].violations,
+            vec![
+                violation(75, 13, (2, 1)),
+                violation(29, 13, (3, 1)),
+                violation(47, 13, (4, 1)),
+                violation(47, 29, (4, 3)),
+            ]
+        );
+
+        let sum: u32 = audits.iter().map(Audit::middle_page).sum();
+        assert_eq!(sum, B::example_output());
+
+        assert_eq!(
+            audits[0].to_string(),
+            "update 4: [75, 97, 47, 61, 53] breaks 97|75 (97 at 1, 75 at 0), \
+             fixed to [97, 75, 47, 61, 53] with middle page 47"
+        );
+
+        Ok(())
+    }
+
     #[test]
     fn b() -> Result<()> {
         let mut b = B;
         b.test_example()
     }
+
+    #[test]
+    fn cycles() {
+        assert_eq!(A::example_input().cycle(), None);
+
+        let mut input = Input {
+            rules: Rules::from_iter([
+                Rule::new(1, 2),
+                Rule::new(2, 3),
+                Rule::new(3, 1),
+                Rule::new(3, 4),
+            ]),
+            updates: vec![
+                Update { pages: vec![4, 3] },
+                Update {
+                    pages: vec![2, 3, 1, 4],
+                },
+            ],
+        };
+
+        let expected = Cycle {
+            pages: vec![1, 2, 3, 1],
+        };
+        assert_eq!(input.cycle(), Some(expected));
+
+        // updates that don't contain the whole cycle can still be fixed
+        input.updates[0].fix(&input.rules).unwrap();
+        assert_eq!(input.updates[0].pages, vec![3, 4]);
+
+        let error = B.solve(input).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "update 2: ordering rules contain a cycle: 1 -> 2 -> 3 -> 1"
+        );
+    }
+
+    #[test]
+    fn contradiction() {
+        let mut input = A::example_input();
+        input.rules.insert(Rule::new(13, 97));
+
+        assert_eq!(
+            input.cycle(),
+            Some(Cycle {
+                pages: vec![13, 97, 13]
+            })
+        );
+
+        // none of the remaining updates contain both pages, so only the rules as a whole are
+        // contradictory and the updates can still be fixed
+        input
+            .updates
+            .retain(|update| !(update.pages.contains(&13) && update.pages.contains(&97)));
+
+        assert_eq!(
+            input.cycle().unwrap().to_string(),
+            "ordering rules contain a cycle: 13 -> 97 -> 13"
+        );
+        assert_eq!(B.solve(input).unwrap(), 47 + 29);
+    }
+
+    /// The original check, scanning the update for both pages of every rule.
+    fn follows_all_reference(update: &Update, rules: &[Rule]) -> bool {
+        rules.iter().all(|rule| {
+            let earlier = update.pages.iter().position(|&p| p == rule.earlier);
+            let later = update.pages.iter().position(|&p| p == rule.later);
+
+            match (earlier, later) {
+                (Some(lower), Some(higher)) => lower < higher,
+                _ => true,
+            }
+        })
+    }
+
+    /// `rules` random rules between `pages` pages, all consistent with one hidden order, and
+    /// `updates` updates of `len` distinct pages. Half the updates are put in the hidden order,
+    /// which makes them valid; the rest are left shuffled.
+    fn generate(pages: u32, rules: usize, updates: usize, len: usize) -> (Vec<Rule>, Vec<Update>) {
+        let mut random = Random::new(0x0505);
+        let mut random = move |modulus: usize| random.below(modulus as u64) as usize;
+
+        let mut order: Vec<u32> = (10..10 + pages).collect();
+        for i in (1..order.len()).rev() {
+            order.swap(i, random(i + 1));
+        }
+
+        let mut rank = HashMap::new();
+        for (i, &page) in order.iter().enumerate() {
+            rank.insert(page, i);
+        }
+
+        let generated_rules = (0..rules)
+            .filter_map(|_| {
+                let (a, b) = (random(order.len()), random(order.len()));
+                (a != b).then(|| Rule::new(order[a.min(b)], order[a.max(b)]))
+            })
+            .collect();
+
+        let generated_updates = (0..updates)
+            .map(|u| {
+                let mut pages = order.clone();
+                for i in 0..len {
+                    let j = i + random(pages.len() - i);
+                    pages.swap(i, j);
+                }
+                pages.truncate(len);
+
+                if u % 2 == 0 {
+                    pages.sort_by_key(|page| rank[page]);
+                }
+
+                Update { pages }
+            })
+            .collect();
+
+        (generated_rules, generated_updates)
+    }
+
+    #[test]
+    fn follows_all_differential() {
+        for (pages, rules, len) in [(5, 10, 3), (20, 100, 7), (50, 1000, 25), (200, 300, 60)] {
+            let (rules, updates) = generate(pages, rules, 50, len);
+            let index = Rules::from_iter(rules.iter().copied());
+
+            for update in &updates {
+                assert_eq!(
+                    update.follows_all(&index),
+                    follows_all_reference(update, &rules),
+                    "{:?}",
+                    update.pages
+                );
+            }
+        }
+    }
+
+    #[test]
+    #[ignore]
+    fn validation_benchmark() {
+        for (rules, len) in [(1_000, 25), (10_000, 100), (100_000, 500)] {
+            let (rules, updates) = generate(2_000, rules, 200, len);
+
+            let (index, index_time) = time(|| Rules::from_iter(rules.iter().copied()));
+            let (valid, indexed_time) =
+                time(|| updates.iter().filter(|u| u.follows_all(&index)).count());
+            let (reference, reference_time) = time(|| {
+                updates
+                    .iter()
+                    .filter(|u| follows_all_reference(u, &rules))
+                    .count()
+            });
+
+            assert_eq!(valid, reference);
+
+            println!(
+                "{:>7} rules, {len:>3} pages per update: index {index_time:?}, \
+                 indexed {indexed_time:?}, reference {reference_time:?}",
+                rules.len()
+            );
+        }
+    }
 }
@@ -0,0 +1,297 @@
+use crate::prelude::*;
+use std::io::BufReader;
+use std::str::FromStr;
+
+#[derive(Clone, Copy)]
+pub struct Robot {
+    x: i32,
+    y: i32,
+    vx: i32,
+    vy: i32,
+}
+
+impl Robot {
+    fn position_after(self, steps: i32, width: i32, height: i32) -> (i32, i32) {
+        let x = (self.x + self.vx * steps).rem_euclid(width);
+        let y = (self.y + self.vy * steps).rem_euclid(height);
+
+        (x, y)
+    }
+}
+
+fn parse_pair(s: &str) -> Result<(i32, i32)> {
+    let (_, pair) = s.split_once('=').ok_or("invalid robot")?;
+    let (a, b) = pair.split_once(',').ok_or("invalid robot")?;
+
+    Ok((a.parse()?, b.parse()?))
+}
+
+impl FromStr for Robot {
+    type Err = crate::Error;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let (p, v) = s.split_once(' ').ok_or("invalid robot")?;
+
+        let (x, y) = parse_pair(p)?;
+        let (vx, vy) = parse_pair(v)?;
+
+        Ok(Self { x, y, vx, vy })
+    }
+}
+
+fn variance(values: impl Iterator<Item = i32>) -> f64 {
+    let values: Vec<f64> = values.map(f64::from).collect();
+    let n = values.len() as f64;
+
+    let mean = values.iter().sum::<f64>() / n;
+    values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / n
+}
+
+pub struct A {
+    width: i32,
+    height: i32,
+}
+
+impl A {
+    pub fn new(width: i32, height: i32) -> Self {
+        Self { width, height }
+    }
+}
+
+impl Default for A {
+    fn default() -> Self {
+        Self::new(101, 103)
+    }
+}
+
+impl Puzzle for A {
+    type Input = Vec<Robot>;
+    type Output = usize;
+
+    fn example_input() -> Self::Input {
+        let input_str = "p=0,4 v=3,-3\n\
+                         p=6,3 v=-1,-3\n\
+                         p=10,3 v=-1,2\n\
+                         p=2,0 v=2,-1\n\
+                         p=0,0 v=1,3\n\
+                         p=3,0 v=-2,-2\n\
+                         p=7,6 v=-1,-3\n\
+                         p=3,0 v=-1,-2\n\
+                         p=9,3 v=2,3\n\
+                         p=7,3 v=-1,2\n\
+                         p=2,4 v=2,-3\n\
+                         p=9,5 v=-3,-3"
+            .to_owned();
+
+        let reader = BufReader::new(input_str.as_bytes());
+        Self::parse_input(reader).unwrap()
+    }
+
+    fn example_output() -> Self::Output {
+        12
+    }
+
+    fn input_file() -> &'static str {
+        "inputs/day14/input"
+    }
+
+    fn parse_input<B>(reader: B) -> Result<Self::Input>
+    where
+        B: BufRead,
+    {
+        let mut robots = Vec::new();
+
+        for line in reader.lines() {
+            let line = line?;
+
+            if line.is_empty() {
+                continue;
+            }
+
+            robots.push(line.parse()?);
+        }
+
+        Ok(robots)
+    }
+
+    fn solve(&mut self, robots: Self::Input) -> Result<Self::Output> {
+        let mid_x = self.width / 2;
+        let mid_y = self.height / 2;
+
+        let mut quadrants = [0; 4];
+
+        for robot in robots {
+            let (x, y) = robot.position_after(100, self.width, self.height);
+
+            if x == mid_x || y == mid_y {
+                continue;
+            }
+
+            let index = (x > mid_x) as usize + 2 * (y > mid_y) as usize;
+            quadrants[index] += 1;
+        }
+
+        Ok(quadrants.iter().product())
+    }
+}
+
+pub struct B {
+    width: i32,
+    height: i32,
+    picture: Option<String>,
+}
+
+impl B {
+    pub fn new(width: i32, height: i32) -> Self {
+        Self {
+            width,
+            height,
+            picture: None,
+        }
+    }
+
+    /// The frame found by the last call to `solve`, if any.
+    pub fn picture(&self) -> Option<&str> {
+        self.picture.as_deref()
+    }
+
+    fn render(&self, robots: &[Robot], steps: i32) -> String {
+        let mut grid = vec![vec!['.'; self.width as usize]; self.height as usize];
+
+        for robot in robots {
+            let (x, y) = robot.position_after(steps, self.width, self.height);
+            grid[y as usize][x as usize] = '#';
+        }
+
+        grid.into_iter()
+            .map(|row| row.into_iter().collect::<String>())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// The step in `0..period` at which the given coordinate is the most tightly clustered.
+    fn most_clustered(
+        robots: &[Robot],
+        period: i32,
+        coordinate: impl Fn(&Robot, i32) -> i32,
+    ) -> i32 {
+        (0..period)
+            .map(|step| {
+                let v = variance(robots.iter().map(|robot| coordinate(robot, step)));
+                (step, v)
+            })
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(step, _)| step)
+            .unwrap_or(0)
+    }
+}
+
+impl Default for B {
+    fn default() -> Self {
+        Self::new(101, 103)
+    }
+}
+
+impl Puzzle for B {
+    type Input = Vec<Robot>;
+    type Output = i32;
+
+    fn example_input() -> Self::Input {
+        // the real example has no picture in it, so build one: a filled triangle plus some noise,
+        // then wind every robot back by the expected number of steps
+        let mut seed = 0x2024_u32;
+        let mut random = move |modulus: i32| {
+            seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12345);
+            ((seed >> 16) % modulus as u32) as i32
+        };
+
+        let mut robots = Vec::new();
+
+        for row in 0..20 {
+            for col in -row..=row {
+                robots.push((50 + col, 40 + row));
+            }
+        }
+
+        for _ in 0..100 {
+            robots.push((random(101), random(103)));
+        }
+
+        let steps = Self::example_output();
+
+        robots
+            .into_iter()
+            .map(|(x, y)| {
+                let vx = random(201) - 100;
+                let vy = random(201) - 100;
+
+                Robot {
+                    x: (x - vx * steps).rem_euclid(101),
+                    y: (y - vy * steps).rem_euclid(103),
+                    vx,
+                    vy,
+                }
+            })
+            .collect()
+    }
+
+    fn example_output() -> Self::Output {
+        6587
+    }
+
+    fn input_file() -> &'static str {
+        "inputs/day14/input"
+    }
+
+    fn parse_input<B>(reader: B) -> Result<Self::Input>
+    where
+        B: BufRead,
+    {
+        A::parse_input(reader)
+    }
+
+    fn solve(&mut self, robots: Self::Input) -> Result<Self::Output> {
+        let (width, height) = (self.width, self.height);
+
+        // x repeats every `width` steps and y every `height` steps, so look for the tightest
+        // cluster along each axis separately and combine the two
+        let best_x = Self::most_clustered(&robots, width, |robot, step| {
+            robot.position_after(step, width, height).0
+        });
+
+        let best_y = Self::most_clustered(&robots, height, |robot, step| {
+            robot.position_after(step, width, height).1
+        });
+
+        let steps = (0..height)
+            .map(|i| best_x + i * width)
+            .find(|step| step % height == best_y)
+            .ok_or("no picture found")?;
+
+        self.picture = Some(self.render(&robots, steps));
+
+        Ok(steps)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a() -> Result<()> {
+        let mut a = A::new(11, 7);
+        a.test_example()
+    }
+
+    #[test]
+    fn b() -> Result<()> {
+        let mut b = B::default();
+        b.test_example()?;
+
+        let picture = b.picture().ok_or("no picture")?;
+        assert!(picture.contains(&"#".repeat(39)));
+
+        Ok(())
+    }
+}
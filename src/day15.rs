@@ -0,0 +1,322 @@
+use crate::prelude::*;
+use std::collections::HashSet;
+use std::io::BufReader;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct Position {
+    x: usize,
+    y: usize,
+}
+
+impl Position {
+    fn step(self, direction: Direction) -> Self {
+        match direction {
+            Direction::Up => Self {
+                x: self.x,
+                y: self.y - 1,
+            },
+
+            Direction::Right => Self {
+                x: self.x + 1,
+                y: self.y,
+            },
+
+            Direction::Down => Self {
+                x: self.x,
+                y: self.y + 1,
+            },
+
+            Direction::Left => Self {
+                x: self.x - 1,
+                y: self.y,
+            },
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Direction {
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+impl Direction {
+    fn is_vertical(self) -> bool {
+        matches!(self, Self::Up | Self::Down)
+    }
+
+    /// How far along this direction a position lies; used to move the farthest cells first.
+    fn progress(self, position: Position) -> isize {
+        match self {
+            Self::Up => -(position.y as isize),
+            Self::Right => position.x as isize,
+            Self::Down => position.y as isize,
+            Self::Left => -(position.x as isize),
+        }
+    }
+}
+
+impl TryFrom<char> for Direction {
+    type Error = &'static str;
+
+    fn try_from(value: char) -> std::result::Result<Self, Self::Error> {
+        match value {
+            '^' => Ok(Self::Up),
+            '>' => Ok(Self::Right),
+            'v' => Ok(Self::Down),
+            '<' => Ok(Self::Left),
+            _ => Err("invalid move"),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct Warehouse {
+    grid: Vec<Vec<char>>,
+    robot: Position,
+    moves: Vec<Direction>,
+}
+
+impl Warehouse {
+    fn get(&self, position: Position) -> char {
+        self.grid[position.y][position.x]
+    }
+
+    fn set(&mut self, position: Position, c: char) {
+        self.grid[position.y][position.x] = c;
+    }
+
+    /// Returns a copy of this warehouse with every tile doubled in width.
+    fn widen(&self) -> Self {
+        let grid = self
+            .grid
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .flat_map(|&c| match c {
+                        '#' => ['#', '#'],
+                        'O' => ['[', ']'],
+                        '@' => ['@', '.'],
+                        _ => ['.', '.'],
+                    })
+                    .collect()
+            })
+            .collect();
+
+        Self {
+            grid,
+            robot: Position {
+                x: self.robot.x * 2,
+                y: self.robot.y,
+            },
+            moves: self.moves.clone(),
+        }
+    }
+
+    fn try_move(&mut self, direction: Direction) {
+        let mut to_move = vec![self.robot];
+        let mut seen = HashSet::from([self.robot]);
+        let mut i = 0;
+
+        while i < to_move.len() {
+            let next = to_move[i].step(direction);
+            i += 1;
+
+            let mut pushed = vec![];
+
+            match self.get(next) {
+                '#' => return,
+                'O' => pushed.push(next),
+                '[' => {
+                    pushed.push(next);
+
+                    if direction.is_vertical() {
+                        pushed.push(next.step(Direction::Right));
+                    }
+                }
+                ']' => {
+                    pushed.push(next);
+
+                    if direction.is_vertical() {
+                        pushed.push(next.step(Direction::Left));
+                    }
+                }
+                _ => {}
+            }
+
+            for position in pushed {
+                if seen.insert(position) {
+                    to_move.push(position);
+                }
+            }
+        }
+
+        // move the cells farthest along first, so nothing gets overwritten
+        to_move.sort_by_key(|&position| -direction.progress(position));
+
+        for position in to_move {
+            let c = self.get(position);
+            self.set(position.step(direction), c);
+            self.set(position, '.');
+        }
+
+        self.robot = self.robot.step(direction);
+    }
+
+    fn run(&mut self) {
+        for i in 0..self.moves.len() {
+            self.try_move(self.moves[i]);
+        }
+    }
+
+    fn gps_sum(&self) -> usize {
+        let mut sum = 0;
+
+        for (y, row) in self.grid.iter().enumerate() {
+            for (x, &c) in row.iter().enumerate() {
+                if c == 'O' || c == '[' {
+                    sum += 100 * y + x;
+                }
+            }
+        }
+
+        sum
+    }
+}
+
+pub struct A;
+
+impl Puzzle for A {
+    type Input = Warehouse;
+    type Output = usize;
+
+    fn example_input() -> Self::Input {
+        let input_str = "########\n\
+                         #..O.O.#\n\
+                         ##@.O..#\n\
+                         #...O..#\n\
+                         #.#.O..#\n\
+                         #...O..#\n\
+                         #......#\n\
+                         ########\n\
+                         \n\
+                         <^^>>>vv<v>>v<<"
+            .to_owned();
+
+        let reader = BufReader::new(input_str.as_bytes());
+        Self::parse_input(reader).unwrap()
+    }
+
+    fn example_output() -> Self::Output {
+        2028
+    }
+
+    fn input_file() -> &'static str {
+        "inputs/day15/input"
+    }
+
+    fn parse_input<B>(reader: B) -> Result<Self::Input>
+    where
+        B: BufRead,
+    {
+        let mut grid = Vec::new();
+        let mut robot = None;
+        let mut moves = Vec::new();
+
+        let mut lines = reader.lines();
+
+        for line in lines.by_ref() {
+            let line = line?;
+
+            if line.is_empty() {
+                break;
+            }
+
+            if let Some(x) = line.find('@') {
+                robot = Some(Position { x, y: grid.len() });
+            }
+
+            grid.push(line.chars().collect());
+        }
+
+        for line in lines {
+            for c in line?.chars() {
+                moves.push(Direction::try_from(c)?);
+            }
+        }
+
+        Ok(Warehouse {
+            grid,
+            robot: robot.ok_or("no robot in warehouse")?,
+            moves,
+        })
+    }
+
+    fn solve(&mut self, mut warehouse: Self::Input) -> Result<Self::Output> {
+        warehouse.run();
+        Ok(warehouse.gps_sum())
+    }
+}
+
+pub struct B;
+
+impl Puzzle for B {
+    type Input = Warehouse;
+    type Output = usize;
+
+    fn example_input() -> Self::Input {
+        let input_str = "#######\n\
+                         #...#.#\n\
+                         #.....#\n\
+                         #..OO@#\n\
+                         #..O..#\n\
+                         #.....#\n\
+                         #######\n\
+                         \n\
+                         <vv<<^^<<^^"
+            .to_owned();
+
+        let reader = BufReader::new(input_str.as_bytes());
+        Self::parse_input(reader).unwrap()
+    }
+
+    fn example_output() -> Self::Output {
+        105 + 207 + 306
+    }
+
+    fn input_file() -> &'static str {
+        "inputs/day15/input"
+    }
+
+    fn parse_input<B>(reader: B) -> Result<Self::Input>
+    where
+        B: BufRead,
+    {
+        A::parse_input(reader)
+    }
+
+    fn solve(&mut self, warehouse: Self::Input) -> Result<Self::Output> {
+        let mut warehouse = warehouse.widen();
+        warehouse.run();
+        Ok(warehouse.gps_sum())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a() -> Result<()> {
+        let mut a = A;
+        a.test_example()
+    }
+
+    #[test]
+    fn b() -> Result<()> {
+        let mut b = B;
+        b.test_example()
+    }
+}
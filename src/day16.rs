@@ -0,0 +1,297 @@
+use crate::prelude::*;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+use std::io::BufReader;
+
+const MOVE_COST: u32 = 1;
+const TURN_COST: u32 = 1000;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+struct Position {
+    x: usize,
+    y: usize,
+}
+
+impl Position {
+    fn step(self, facing: Facing) -> Self {
+        match facing {
+            Facing::North => Self {
+                x: self.x,
+                y: self.y - 1,
+            },
+
+            Facing::East => Self {
+                x: self.x + 1,
+                y: self.y,
+            },
+
+            Facing::South => Self {
+                x: self.x,
+                y: self.y + 1,
+            },
+
+            Facing::West => Self {
+                x: self.x - 1,
+                y: self.y,
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+enum Facing {
+    North,
+    East,
+    South,
+    West,
+}
+
+impl Facing {
+    fn turns(self) -> [Self; 2] {
+        match self {
+            Self::North | Self::South => [Self::East, Self::West],
+            Self::East | Self::West => [Self::North, Self::South],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+struct State {
+    position: Position,
+    facing: Facing,
+}
+
+impl State {
+    /// Every state reachable from this one in a single action, with the cost of that action.
+    fn successors(self) -> [(Self, u32); 3] {
+        let [left, right] = self.facing.turns();
+
+        [
+            (
+                Self {
+                    position: self.position.step(self.facing),
+                    facing: self.facing,
+                },
+                MOVE_COST,
+            ),
+            (
+                Self {
+                    position: self.position,
+                    facing: left,
+                },
+                TURN_COST,
+            ),
+            (
+                Self {
+                    position: self.position,
+                    facing: right,
+                },
+                TURN_COST,
+            ),
+        ]
+    }
+}
+
+pub struct Maze {
+    walls: HashSet<Position>,
+    start: Position,
+    end: Position,
+}
+
+struct Search {
+    best: u32,
+    tiles: usize,
+}
+
+impl Maze {
+    /// Dijkstra over (position, facing), keeping every predecessor that reaches a state at its
+    /// optimal cost so all best paths can be walked back from the end.
+    fn search(&self) -> Result<Search> {
+        let start = State {
+            position: self.start,
+            facing: Facing::East,
+        };
+
+        let mut costs = HashMap::from([(start, 0)]);
+        let mut predecessors: HashMap<State, Vec<State>> = HashMap::new();
+        let mut queue = BinaryHeap::from([Reverse((0, start))]);
+
+        while let Some(Reverse((cost, state))) = queue.pop() {
+            if costs.get(&state).is_some_and(|&known| cost > known) {
+                continue;
+            }
+
+            for (next, step_cost) in state.successors() {
+                if self.walls.contains(&next.position) {
+                    continue;
+                }
+
+                let next_cost = cost + step_cost;
+
+                match costs.get(&next) {
+                    Some(&known) if next_cost > known => {}
+
+                    Some(&known) if next_cost == known => {
+                        predecessors.entry(next).or_default().push(state);
+                    }
+
+                    _ => {
+                        costs.insert(next, next_cost);
+                        predecessors.insert(next, vec![state]);
+                        queue.push(Reverse((next_cost, next)));
+                    }
+                }
+            }
+        }
+
+        let best = costs
+            .iter()
+            .filter(|(state, _)| state.position == self.end)
+            .map(|(_, &cost)| cost)
+            .min()
+            .ok_or("end is unreachable")?;
+
+        let mut stack: Vec<State> = costs
+            .iter()
+            .filter(|(state, &cost)| state.position == self.end && cost == best)
+            .map(|(&state, _)| state)
+            .collect();
+
+        let mut seen: HashSet<State> = stack.iter().copied().collect();
+
+        while let Some(state) = stack.pop() {
+            for &previous in predecessors.get(&state).into_iter().flatten() {
+                if seen.insert(previous) {
+                    stack.push(previous);
+                }
+            }
+        }
+
+        let tiles = seen
+            .iter()
+            .map(|state| state.position)
+            .collect::<HashSet<_>>()
+            .len();
+
+        Ok(Search { best, tiles })
+    }
+}
+
+pub struct A;
+
+impl Puzzle for A {
+    type Input = Maze;
+    type Output = u32;
+
+    fn example_input() -> Self::Input {
+        let input_str = "###############\n\
+                         #.......#....E#\n\
+                         #.#.###.#.###.#\n\
+                         #.....#.#...#.#\n\
+                         #.###.#####.#.#\n\
+                         #.#.#.......#.#\n\
+                         #.#.#####.###.#\n\
+                         #...........#.#\n\
+                         ###.#.#####.#.#\n\
+                         #...#.....#.#.#\n\
+                         #.#.#.###.#.#.#\n\
+                         #.....#...#.#.#\n\
+                         #.###.#.#.#.#.#\n\
+                         #S..#.....#...#\n\
+                         ###############"
+            .to_owned();
+
+        let reader = BufReader::new(input_str.as_bytes());
+        Self::parse_input(reader).unwrap()
+    }
+
+    fn example_output() -> Self::Output {
+        7036
+    }
+
+    fn input_file() -> &'static str {
+        "inputs/day16/input"
+    }
+
+    fn parse_input<B>(reader: B) -> Result<Self::Input>
+    where
+        B: BufRead,
+    {
+        let mut walls = HashSet::new();
+        let mut start = None;
+        let mut end = None;
+
+        for (y, line) in reader.lines().enumerate() {
+            for (x, c) in line?.chars().enumerate() {
+                match c {
+                    '#' => {
+                        walls.insert(Position { x, y });
+                    }
+
+                    'S' => start = Some(Position { x, y }),
+
+                    'E' => end = Some(Position { x, y }),
+
+                    _ => {}
+                }
+            }
+        }
+
+        Ok(Maze {
+            walls,
+            start: start.ok_or("maze has no start")?,
+            end: end.ok_or("maze has no end")?,
+        })
+    }
+
+    fn solve(&mut self, maze: Self::Input) -> Result<Self::Output> {
+        Ok(maze.search()?.best)
+    }
+}
+
+pub struct B;
+
+impl Puzzle for B {
+    type Input = Maze;
+    type Output = usize;
+
+    fn example_input() -> Self::Input {
+        A::example_input()
+    }
+
+    fn example_output() -> Self::Output {
+        45
+    }
+
+    fn input_file() -> &'static str {
+        "inputs/day16/input"
+    }
+
+    fn parse_input<B>(reader: B) -> Result<Self::Input>
+    where
+        B: BufRead,
+    {
+        A::parse_input(reader)
+    }
+
+    fn solve(&mut self, maze: Self::Input) -> Result<Self::Output> {
+        Ok(maze.search()?.tiles)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a() -> Result<()> {
+        let mut a = A;
+        a.test_example()
+    }
+
+    #[test]
+    fn b() -> Result<()> {
+        let mut b = B;
+        b.test_example()
+    }
+}
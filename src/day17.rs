@@ -0,0 +1,300 @@
+use crate::prelude::*;
+use std::io::BufReader;
+
+/// Instructions a program may execute before it's assumed to loop forever. Puzzle programs run
+/// one loop of a handful of instructions per three bits of register A.
+const MAX_STEPS: usize = 100_000;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Opcode {
+    Adv,
+    Bxl,
+    Bst,
+    Jnz,
+    Bxc,
+    Out,
+    Bdv,
+    Cdv,
+}
+
+impl TryFrom<u8> for Opcode {
+    type Error = &'static str;
+
+    fn try_from(value: u8) -> std::result::Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Self::Adv),
+            1 => Ok(Self::Bxl),
+            2 => Ok(Self::Bst),
+            3 => Ok(Self::Jnz),
+            4 => Ok(Self::Bxc),
+            5 => Ok(Self::Out),
+            6 => Ok(Self::Bdv),
+            7 => Ok(Self::Cdv),
+            _ => Err("invalid opcode"),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct Computer {
+    a: u64,
+    b: u64,
+    c: u64,
+    program: Vec<u8>,
+}
+
+impl Computer {
+    fn combo(&self, operand: u8) -> Result<u64> {
+        match operand {
+            0..=3 => Ok(operand as u64),
+            4 => Ok(self.a),
+            5 => Ok(self.b),
+            6 => Ok(self.c),
+            _ => Err("invalid combo operand".into()),
+        }
+    }
+
+    fn divide(&self, operand: u8) -> Result<u64> {
+        let shift = self.combo(operand)?;
+
+        // anything shifted by 64 or more bits is zero anyway
+        Ok(self.a.checked_shr(shift as u32).unwrap_or(0))
+    }
+
+    /// Runs the program from the start with the given value in register A, returning its output.
+    /// Fails if it doesn't halt within [`MAX_STEPS`] instructions.
+    fn run(&self, a: u64) -> Result<Vec<u8>> {
+        self.try_run(a)?
+            .ok_or_else(|| format!("program didn't halt within {MAX_STEPS} instructions").into())
+    }
+
+    /// Like [`Computer::run`], but with `None` for a program that doesn't halt in time.
+    fn try_run(&self, a: u64) -> Result<Option<Vec<u8>>> {
+        let mut computer = self.clone();
+        computer.a = a;
+
+        let mut output = Vec::new();
+        let mut ip = 0;
+        let mut steps = 0;
+
+        while ip + 1 < computer.program.len() {
+            steps += 1;
+
+            if steps > MAX_STEPS {
+                return Ok(None);
+            }
+
+            let opcode = Opcode::try_from(computer.program[ip])?;
+            let operand = computer.program[ip + 1];
+
+            match opcode {
+                Opcode::Adv => computer.a = computer.divide(operand)?,
+                Opcode::Bxl => computer.b ^= operand as u64,
+                Opcode::Bst => computer.b = computer.combo(operand)? % 8,
+                Opcode::Jnz => {
+                    if computer.a != 0 {
+                        ip = operand as usize;
+                        continue;
+                    }
+                }
+                Opcode::Bxc => computer.b ^= computer.c,
+                Opcode::Out => output.push((computer.combo(operand)? % 8) as u8),
+                Opcode::Bdv => computer.b = computer.divide(operand)?,
+                Opcode::Cdv => computer.c = computer.divide(operand)?,
+            }
+
+            ip += 2;
+        }
+
+        Ok(Some(output))
+    }
+}
+
+pub struct A;
+
+impl Puzzle for A {
+    type Input = Computer;
+    type Output = String;
+
+    fn example_input() -> Self::Input {
+        let input_str = "Register A: 729\n\
+                         Register B: 0\n\
+                         Register C: 0\n\
+                         \n\
+                         Program: 0,1,5,4,3,0"
+            .to_owned();
+
+        let reader = BufReader::new(input_str.as_bytes());
+        Self::parse_input(reader).unwrap()
+    }
+
+    fn example_output() -> Self::Output {
+        "4,6,3,5,6,3,5,2,1,0".to_owned()
+    }
+
+    fn input_file() -> &'static str {
+        "inputs/day17/input"
+    }
+
+    fn parse_input<B>(reader: B) -> Result<Self::Input>
+    where
+        B: BufRead,
+    {
+        let mut registers = [0; 3];
+        let mut program = Vec::new();
+
+        for line in reader.lines() {
+            let line = line?;
+
+            if let Some((name, value)) = line.split_once(": ") {
+                match name {
+                    "Register A" => registers[0] = value.parse()?,
+                    "Register B" => registers[1] = value.parse()?,
+                    "Register C" => registers[2] = value.parse()?,
+                    "Program" => {
+                        for n in value.split(',') {
+                            program.push(n.parse()?);
+                        }
+                    }
+                    _ => return Err("invalid input".into()),
+                }
+            }
+        }
+
+        let [a, b, c] = registers;
+
+        Ok(Computer { a, b, c, program })
+    }
+
+    fn solve(&mut self, computer: Self::Input) -> Result<Self::Output> {
+        let output = computer
+            .run(computer.a)?
+            .iter()
+            .map(|n| n.to_string())
+            .collect::<Vec<_>>()
+            .join(",");
+
+        Ok(output)
+    }
+}
+
+pub struct B;
+
+impl Puzzle for B {
+    type Input = Computer;
+    type Output = u64;
+
+    fn example_input() -> Self::Input {
+        let input_str = "Register A: 2024\n\
+                         Register B: 0\n\
+                         Register C: 0\n\
+                         \n\
+                         Program: 0,3,5,4,3,0"
+            .to_owned();
+
+        let reader = BufReader::new(input_str.as_bytes());
+        Self::parse_input(reader).unwrap()
+    }
+
+    fn example_output() -> Self::Output {
+        117440
+    }
+
+    fn input_file() -> &'static str {
+        "inputs/day17/input"
+    }
+
+    fn parse_input<B>(reader: B) -> Result<Self::Input>
+    where
+        B: BufRead,
+    {
+        A::parse_input(reader)
+    }
+
+    fn solve(&mut self, computer: Self::Input) -> Result<Self::Output> {
+        // the programs consume register A three bits per output, with the last output depending
+        // only on the highest bits; so build A up three bits at a time, matching the program's
+        // tail from the back
+        let mut candidates = vec![0];
+        let mut endless = 0;
+
+        for i in (0..computer.program.len()).rev() {
+            let expected = &computer.program[i..];
+            let mut next = Vec::new();
+
+            for candidate in candidates {
+                for bits in 0..8 {
+                    let a = candidate * 8 + bits;
+
+                    // a candidate that doesn't halt can't output anything, but others still might
+                    match computer.try_run(a)? {
+                        Some(output) if output == expected => next.push(a),
+                        Some(_) => {}
+                        None => endless += 1,
+                    }
+                }
+            }
+
+            candidates = next;
+        }
+
+        candidates.into_iter().min().ok_or_else(|| {
+            format!(
+                "no value of register A makes the program output itself \
+                 ({endless} candidates didn't halt)"
+            )
+            .into()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn computer(a: u64, b: u64, c: u64, program: &[u8]) -> Computer {
+        Computer {
+            a,
+            b,
+            c,
+            program: program.to_vec(),
+        }
+    }
+
+    #[test]
+    fn a() -> Result<()> {
+        let mut a = A;
+        a.test_example()
+    }
+
+    #[test]
+    fn instructions() -> Result<()> {
+        let c = computer(10, 0, 0, &[5, 0, 5, 1, 5, 4]);
+        assert_eq!(c.run(10)?, vec![0, 1, 2]);
+
+        let c = computer(2024, 0, 0, &[0, 1, 5, 4, 3, 0]);
+        assert_eq!(c.run(2024)?, vec![4, 2, 5, 6, 7, 7, 7, 7, 3, 1, 0]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn step_limit() {
+        let c = computer(1, 0, 0, &[3, 0]);
+        assert!(c.run(1).is_err());
+        assert_eq!(c.run(0).unwrap(), vec![]);
+
+        // every candidate but 0 loops forever; they're skipped, so the search runs out of
+        // candidates instead of stopping at the first one
+        assert_eq!(
+            B.solve(c).unwrap_err().to_string(),
+            "no value of register A makes the program output itself (7 candidates didn't halt)"
+        );
+    }
+
+    #[test]
+    fn b() -> Result<()> {
+        let mut b = B;
+        b.test_example()
+    }
+}
@@ -0,0 +1,205 @@
+use crate::prelude::*;
+use std::collections::{HashSet, VecDeque};
+use std::io::BufReader;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Position {
+    x: usize,
+    y: usize,
+}
+
+impl Position {
+    fn neighbours(self, size: usize) -> impl Iterator<Item = Self> {
+        let Self { x, y } = self;
+
+        [
+            (x.checked_sub(1), Some(y)),
+            (Some(x + 1), Some(y)),
+            (Some(x), y.checked_sub(1)),
+            (Some(x), Some(y + 1)),
+        ]
+        .into_iter()
+        .filter_map(move |pair| match pair {
+            (Some(x), Some(y)) if x < size && y < size => Some(Self { x, y }),
+            _ => None,
+        })
+    }
+}
+
+/// Length of the shortest path from the top left to the bottom right corner of a `size` by
+/// `size` grid, avoiding `corrupted`, or `None` if the exit can't be reached.
+fn shortest_path(size: usize, corrupted: &[Position]) -> Option<usize> {
+    let corrupted: HashSet<Position> = corrupted.iter().copied().collect();
+
+    let start = Position { x: 0, y: 0 };
+    let exit = Position {
+        x: size - 1,
+        y: size - 1,
+    };
+
+    if corrupted.contains(&start) {
+        return None;
+    }
+
+    let mut seen = HashSet::from([start]);
+    let mut queue = VecDeque::from([(start, 0)]);
+
+    while let Some((position, steps)) = queue.pop_front() {
+        if position == exit {
+            return Some(steps);
+        }
+
+        for next in position.neighbours(size) {
+            if !corrupted.contains(&next) && seen.insert(next) {
+                queue.push_back((next, steps + 1));
+            }
+        }
+    }
+
+    None
+}
+
+pub struct A {
+    size: usize,
+    bytes: usize,
+}
+
+impl A {
+    pub fn new(size: usize, bytes: usize) -> Self {
+        Self { size, bytes }
+    }
+}
+
+impl Default for A {
+    fn default() -> Self {
+        Self::new(71, 1024)
+    }
+}
+
+impl Puzzle for A {
+    type Input = Vec<Position>;
+    type Output = usize;
+
+    fn example_input() -> Self::Input {
+        let input_str = "5,4\n4,2\n4,5\n3,0\n2,1\n6,3\n2,4\n1,5\n0,6\n3,3\n2,6\n5,1\n1,2\n\
+                         5,5\n2,5\n6,5\n1,4\n0,4\n6,4\n1,1\n6,1\n1,0\n0,5\n1,6\n2,0"
+            .to_owned();
+
+        let reader = BufReader::new(input_str.as_bytes());
+        Self::parse_input(reader).unwrap()
+    }
+
+    fn example_output() -> Self::Output {
+        22
+    }
+
+    fn input_file() -> &'static str {
+        "inputs/day18/input"
+    }
+
+    fn parse_input<B>(reader: B) -> Result<Self::Input>
+    where
+        B: BufRead,
+    {
+        let mut bytes = Vec::new();
+
+        for line in reader.lines() {
+            let line = line?;
+
+            let (x, y) = line.split_once(',').ok_or("invalid input")?;
+
+            bytes.push(Position {
+                x: x.parse()?,
+                y: y.parse()?,
+            });
+        }
+
+        Ok(bytes)
+    }
+
+    fn solve(&mut self, bytes: Self::Input) -> Result<Self::Output> {
+        let fallen = &bytes[..self.bytes.min(bytes.len())];
+
+        Ok(shortest_path(self.size, fallen).ok_or("exit is unreachable")?)
+    }
+}
+
+pub struct B {
+    size: usize,
+}
+
+impl B {
+    pub fn new(size: usize) -> Self {
+        Self { size }
+    }
+}
+
+impl Default for B {
+    fn default() -> Self {
+        Self::new(71)
+    }
+}
+
+impl Puzzle for B {
+    type Input = Vec<Position>;
+    type Output = String;
+
+    fn example_input() -> Self::Input {
+        A::example_input()
+    }
+
+    fn example_output() -> Self::Output {
+        "6,1".to_owned()
+    }
+
+    fn input_file() -> &'static str {
+        "inputs/day18/input"
+    }
+
+    fn parse_input<B>(reader: B) -> Result<Self::Input>
+    where
+        B: BufRead,
+    {
+        A::parse_input(reader)
+    }
+
+    fn solve(&mut self, bytes: Self::Input) -> Result<Self::Output> {
+        // binary search for the smallest number of fallen bytes that cuts off the exit
+        let mut lo = 0;
+        let mut hi = bytes.len();
+
+        if shortest_path(self.size, &bytes).is_some() {
+            return Err("exit is never cut off".into());
+        }
+
+        while lo < hi {
+            let mid = (lo + hi) / 2;
+
+            if shortest_path(self.size, &bytes[..=mid]).is_some() {
+                lo = mid + 1;
+            } else {
+                hi = mid;
+            }
+        }
+
+        let Position { x, y } = bytes[lo];
+        Ok(format!("{x},{y}"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a() -> Result<()> {
+        let mut a = A::new(7, 12);
+        a.test_example()
+    }
+
+    #[test]
+    fn b() -> Result<()> {
+        let mut b = B::new(7);
+        b.test_example()
+    }
+}
@@ -0,0 +1,197 @@
+use crate::prelude::*;
+use std::collections::HashMap;
+use std::io::BufReader;
+
+#[derive(Default)]
+struct Node {
+    children: HashMap<u8, usize>,
+    terminal: bool,
+}
+
+/// A prefix tree over the towel patterns, so every pattern that starts at a given position of a
+/// design can be found in a single walk.
+struct Trie {
+    nodes: Vec<Node>,
+}
+
+impl Trie {
+    fn new() -> Self {
+        Self {
+            nodes: vec![Node::default()],
+        }
+    }
+
+    fn insert(&mut self, pattern: &[u8]) {
+        let mut current = 0;
+
+        for &c in pattern {
+            current = match self.nodes[current].children.get(&c) {
+                Some(&next) => next,
+                None => {
+                    self.nodes.push(Node::default());
+                    let next = self.nodes.len() - 1;
+                    self.nodes[current].children.insert(c, next);
+                    next
+                }
+            };
+        }
+
+        self.nodes[current].terminal = true;
+    }
+
+    /// Lengths of all patterns that are a prefix of `s`.
+    fn prefixes<'a>(&'a self, s: &'a [u8]) -> impl Iterator<Item = usize> + 'a {
+        let mut current = Some(0);
+
+        s.iter()
+            .enumerate()
+            .map_while(move |(i, c)| {
+                let next = *self.nodes[current?].children.get(c)?;
+                current = Some(next);
+
+                Some((i + 1, self.nodes[next].terminal))
+            })
+            .filter_map(|(len, terminal)| terminal.then_some(len))
+    }
+}
+
+pub struct Input {
+    patterns: Trie,
+    designs: Vec<String>,
+}
+
+impl Input {
+    /// Number of distinct ways `design` can be built from the available patterns.
+    fn arrangements(&self, design: &str) -> u64 {
+        let design = design.as_bytes();
+
+        // ways[i] is the number of arrangements for the suffix starting at i
+        let mut ways = vec![0; design.len() + 1];
+        ways[design.len()] = 1;
+
+        for i in (0..design.len()).rev() {
+            ways[i] = self
+                .patterns
+                .prefixes(&design[i..])
+                .map(|len| ways[i + len])
+                .sum();
+        }
+
+        ways[0]
+    }
+}
+
+pub struct A;
+
+impl Puzzle for A {
+    type Input = Input;
+    type Output = usize;
+
+    fn example_input() -> Self::Input {
+        let input_str = "r, wr, b, g, bwu, rb, gb, br\n\
+                         \n\
+                         brwrr\n\
+                         bggr\n\
+                         gbbr\n\
+                         rrbgbr\n\
+                         ubwu\n\
+                         bwurrg\n\
+                         brgr\n\
+                         bbrgwb"
+            .to_owned();
+
+        let reader = BufReader::new(input_str.as_bytes());
+        Self::parse_input(reader).unwrap()
+    }
+
+    fn example_output() -> Self::Output {
+        6
+    }
+
+    fn input_file() -> &'static str {
+        "inputs/day19/input"
+    }
+
+    fn parse_input<B>(reader: B) -> Result<Self::Input>
+    where
+        B: BufRead,
+    {
+        let mut lines = reader.lines();
+        let mut patterns = Trie::new();
+
+        for pattern in lines.next().ok_or("invalid input")??.split(", ") {
+            patterns.insert(pattern.as_bytes());
+        }
+
+        let mut designs = Vec::new();
+
+        for line in lines {
+            let line = line?;
+
+            if !line.is_empty() {
+                designs.push(line);
+            }
+        }
+
+        Ok(Input { patterns, designs })
+    }
+
+    fn solve(&mut self, input: Self::Input) -> Result<Self::Output> {
+        Ok(input
+            .designs
+            .iter()
+            .filter(|design| input.arrangements(design) > 0)
+            .count())
+    }
+}
+
+pub struct B;
+
+impl Puzzle for B {
+    type Input = Input;
+    type Output = u64;
+
+    fn example_input() -> Self::Input {
+        A::example_input()
+    }
+
+    fn example_output() -> Self::Output {
+        16
+    }
+
+    fn input_file() -> &'static str {
+        "inputs/day19/input"
+    }
+
+    fn parse_input<B>(reader: B) -> Result<Self::Input>
+    where
+        B: BufRead,
+    {
+        A::parse_input(reader)
+    }
+
+    fn solve(&mut self, input: Self::Input) -> Result<Self::Output> {
+        Ok(input
+            .designs
+            .iter()
+            .map(|design| input.arrangements(design))
+            .sum())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a() -> Result<()> {
+        let mut a = A;
+        a.test_example()
+    }
+
+    #[test]
+    fn b() -> Result<()> {
+        let mut b = B;
+        b.test_example()
+    }
+}
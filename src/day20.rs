@@ -0,0 +1,227 @@
+use crate::prelude::*;
+use std::collections::HashSet;
+use std::io::BufReader;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct Position {
+    x: usize,
+    y: usize,
+}
+
+impl Position {
+    fn neighbours(self) -> [Self; 4] {
+        let Self { x, y } = self;
+
+        [
+            Self { x: x - 1, y },
+            Self { x: x + 1, y },
+            Self { x, y: y - 1 },
+            Self { x, y: y + 1 },
+        ]
+    }
+
+    fn distance(self, other: Self) -> usize {
+        self.x.abs_diff(other.x) + self.y.abs_diff(other.y)
+    }
+}
+
+pub struct Track {
+    /// Every position on the track, in the order they're raced through; the index of a position
+    /// is its distance from the start.
+    path: Vec<Position>,
+}
+
+impl Track {
+    /// Number of cheats of at most `max_len` picoseconds that save at least `threshold`.
+    fn count_cheats(&self, max_len: usize, threshold: usize) -> usize {
+        let mut count = 0;
+
+        for (i, &from) in self.path.iter().enumerate() {
+            // a cheat has to save at least `threshold`, so there's no point looking any closer
+            for (j, &to) in self.path.iter().enumerate().skip(i + threshold.max(1)) {
+                let distance = from.distance(to);
+
+                if distance <= max_len && j - i >= distance + threshold {
+                    count += 1;
+                }
+            }
+        }
+
+        count
+    }
+}
+
+pub struct A {
+    threshold: usize,
+}
+
+impl A {
+    pub fn new(threshold: usize) -> Self {
+        Self { threshold }
+    }
+}
+
+impl Default for A {
+    fn default() -> Self {
+        Self::new(100)
+    }
+}
+
+impl Puzzle for A {
+    type Input = Track;
+    type Output = usize;
+
+    fn example_input() -> Self::Input {
+        let input_str = "###############\n\
+                         #...#...#.....#\n\
+                         #.#.#.#.#.###.#\n\
+                         #S#...#.#.#...#\n\
+                         #######.#.#.###\n\
+                         #######.#.#...#\n\
+                         #######.#.###.#\n\
+                         ###..E#...#...#\n\
+                         ###.#######.###\n\
+                         #...###...#...#\n\
+                         #.#####.#.###.#\n\
+                         #.#...#.#.#...#\n\
+                         #.#.#.#.#.#.###\n\
+                         #...#...#...###\n\
+                         ###############"
+            .to_owned();
+
+        let reader = BufReader::new(input_str.as_bytes());
+        Self::parse_input(reader).unwrap()
+    }
+
+    fn example_output() -> Self::Output {
+        // cheats saving 20, 36, 38, 40 and 64 picoseconds
+        5
+    }
+
+    fn input_file() -> &'static str {
+        "inputs/day20/input"
+    }
+
+    fn parse_input<B>(reader: B) -> Result<Self::Input>
+    where
+        B: BufRead,
+    {
+        let mut track = HashSet::new();
+        let mut start = None;
+        let mut end = None;
+
+        for (y, line) in reader.lines().enumerate() {
+            for (x, c) in line?.chars().enumerate() {
+                let position = Position { x, y };
+
+                match c {
+                    '.' => {}
+                    'S' => start = Some(position),
+                    'E' => end = Some(position),
+                    _ => continue,
+                }
+
+                track.insert(position);
+            }
+        }
+
+        let start = start.ok_or("track has no start")?;
+        let end = end.ok_or("track has no end")?;
+
+        // there's only a single path, so just follow it
+        let mut path = vec![start];
+        let mut previous = start;
+
+        while let Some(&current) = path.last() {
+            if current == end {
+                break;
+            }
+
+            let next = current
+                .neighbours()
+                .into_iter()
+                .find(|&next| next != previous && track.contains(&next))
+                .ok_or("track is broken")?;
+
+            previous = current;
+            path.push(next);
+        }
+
+        Ok(Track { path })
+    }
+
+    fn solve(&mut self, track: Self::Input) -> Result<Self::Output> {
+        Ok(track.count_cheats(2, self.threshold))
+    }
+}
+
+pub struct B {
+    threshold: usize,
+}
+
+impl B {
+    pub fn new(threshold: usize) -> Self {
+        Self { threshold }
+    }
+}
+
+impl Default for B {
+    fn default() -> Self {
+        Self::new(100)
+    }
+}
+
+impl Puzzle for B {
+    type Input = Track;
+    type Output = usize;
+
+    fn example_input() -> Self::Input {
+        A::example_input()
+    }
+
+    fn example_output() -> Self::Output {
+        285
+    }
+
+    fn input_file() -> &'static str {
+        "inputs/day20/input"
+    }
+
+    fn parse_input<B>(reader: B) -> Result<Self::Input>
+    where
+        B: BufRead,
+    {
+        A::parse_input(reader)
+    }
+
+    fn solve(&mut self, track: Self::Input) -> Result<Self::Output> {
+        Ok(track.count_cheats(20, self.threshold))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a() -> Result<()> {
+        let mut a = A::new(20);
+        a.test_example()
+    }
+
+    #[test]
+    fn thresholds() {
+        let track = A::example_input();
+
+        assert_eq!(track.count_cheats(2, 64), 1);
+        assert_eq!(track.count_cheats(2, 2), 44);
+        assert_eq!(track.count_cheats(20, 76), 3);
+        assert_eq!(track.count_cheats(20, 74), 7);
+    }
+
+    #[test]
+    fn b() -> Result<()> {
+        let mut b = B::new(50);
+        b.test_example()
+    }
+}
@@ -0,0 +1,221 @@
+use crate::prelude::*;
+use std::collections::HashMap;
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+enum Keypad {
+    Numeric,
+    Directional,
+}
+
+impl Keypad {
+    /// Rows of buttons, top to bottom; the gap no robot arm may pass over is a space.
+    fn layout(self) -> &'static [&'static str] {
+        match self {
+            Self::Numeric => &["789", "456", "123", " 0A"],
+            Self::Directional => &[" ^A", "<v>"],
+        }
+    }
+
+    fn position(self, button: char) -> Result<(usize, usize)> {
+        for (row, line) in self.layout().iter().enumerate() {
+            if let Some(col) = line.find(button) {
+                return Ok((row, col));
+            }
+        }
+
+        Err(format!("no button {button:?} on keypad").into())
+    }
+
+    /// The directional sequences (each ending in a press of `A`) that move the arm from `from`
+    /// to `to` without passing over the gap. Zig-zagging is never cheaper than doing all the
+    /// horizontal moves first or all the vertical moves first, so only those two are tried.
+    fn paths(self, from: char, to: char) -> Result<Vec<String>> {
+        let (from_row, from_col) = self.position(from)?;
+        let (to_row, to_col) = self.position(to)?;
+        let gap = self.position(' ')?;
+
+        let vertical = if to_row > from_row { "v" } else { "^" }.repeat(from_row.abs_diff(to_row));
+        let horizontal =
+            if to_col > from_col { ">" } else { "<" }.repeat(from_col.abs_diff(to_col));
+
+        let mut paths = Vec::new();
+
+        // horizontal first passes the corner at (from_row, to_col)
+        if gap != (from_row, to_col) {
+            paths.push(format!("{horizontal}{vertical}A"));
+        }
+
+        // vertical first passes the corner at (to_row, from_col)
+        if gap != (to_row, from_col) {
+            paths.push(format!("{vertical}{horizontal}A"));
+        }
+
+        paths.dedup();
+        Ok(paths)
+    }
+}
+
+/// A chain of directional keypads, each operated by the one above it, with a human at the top.
+struct Chain {
+    costs: HashMap<(Keypad, char, char, usize), u64>,
+}
+
+impl Chain {
+    fn new() -> Self {
+        Self {
+            costs: HashMap::new(),
+        }
+    }
+
+    /// Number of human button presses needed to type `sequence` on `keypad`, when there are
+    /// `depth` directional keypads between it and the human.
+    fn sequence_cost(&mut self, keypad: Keypad, sequence: &str, depth: usize) -> Result<u64> {
+        if depth == 0 {
+            return Ok(sequence.len() as u64);
+        }
+
+        let mut cost = 0;
+        let mut current = 'A';
+
+        for next in sequence.chars() {
+            cost += self.move_cost(keypad, current, next, depth)?;
+            current = next;
+        }
+
+        Ok(cost)
+    }
+
+    fn move_cost(&mut self, keypad: Keypad, from: char, to: char, depth: usize) -> Result<u64> {
+        if let Some(&cost) = self.costs.get(&(keypad, from, to, depth)) {
+            return Ok(cost);
+        }
+
+        let mut best = u64::MAX;
+
+        for path in keypad.paths(from, to)? {
+            best = best.min(self.sequence_cost(Keypad::Directional, &path, depth - 1)?);
+        }
+
+        self.costs.insert((keypad, from, to, depth), best);
+
+        Ok(best)
+    }
+}
+
+/// Sum of the complexities of `codes`, typed through `robots` robot-operated directional keypads.
+fn complexity_sum(codes: &[String], robots: usize) -> Result<u64> {
+    let mut chain = Chain::new();
+    let mut sum = 0;
+
+    for code in codes {
+        // the robots' keypads plus the one the human is using
+        let presses = chain.sequence_cost(Keypad::Numeric, code, robots + 1)?;
+        let numeric: u64 = code.trim_end_matches('A').parse()?;
+
+        sum += presses * numeric;
+    }
+
+    Ok(sum)
+}
+
+pub struct A;
+
+impl Puzzle for A {
+    type Input = Vec<String>;
+    type Output = u64;
+
+    fn example_input() -> Self::Input {
+        ["029A", "980A", "179A", "456A", "379A"]
+            .into_iter()
+            .map(String::from)
+            .collect()
+    }
+
+    fn example_output() -> Self::Output {
+        126384
+    }
+
+    fn input_file() -> &'static str {
+        "inputs/day21/input"
+    }
+
+    fn parse_input<B>(reader: B) -> Result<Self::Input>
+    where
+        B: BufRead,
+    {
+        let mut codes = Vec::new();
+
+        for line in reader.lines() {
+            let line = line?;
+
+            if !line.is_empty() {
+                codes.push(line);
+            }
+        }
+
+        Ok(codes)
+    }
+
+    fn solve(&mut self, codes: Self::Input) -> Result<Self::Output> {
+        complexity_sum(&codes, 2)
+    }
+}
+
+pub struct B;
+
+impl Puzzle for B {
+    type Input = Vec<String>;
+    type Output = u64;
+
+    fn example_input() -> Self::Input {
+        A::example_input()
+    }
+
+    fn example_output() -> Self::Output {
+        154115708116294
+    }
+
+    fn input_file() -> &'static str {
+        "inputs/day21/input"
+    }
+
+    fn parse_input<B>(reader: B) -> Result<Self::Input>
+    where
+        B: BufRead,
+    {
+        A::parse_input(reader)
+    }
+
+    fn solve(&mut self, codes: Self::Input) -> Result<Self::Output> {
+        complexity_sum(&codes, 25)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a() -> Result<()> {
+        let mut a = A;
+        a.test_example()
+    }
+
+    #[test]
+    fn presses() -> Result<()> {
+        let mut chain = Chain::new();
+
+        // with one, two and three directional keypads between the numeric keypad and the human
+        assert_eq!(chain.sequence_cost(Keypad::Numeric, "029A", 1)?, 12);
+        assert_eq!(chain.sequence_cost(Keypad::Numeric, "029A", 2)?, 28);
+        assert_eq!(chain.sequence_cost(Keypad::Numeric, "029A", 3)?, 68);
+
+        Ok(())
+    }
+
+    #[test]
+    fn b() -> Result<()> {
+        let mut b = B;
+        b.test_example()
+    }
+}
@@ -0,0 +1,142 @@
+use crate::prelude::*;
+
+const PRUNE: u64 = 16777216;
+const ROUNDS: usize = 2000;
+
+/// Number of distinct values a single price change can take, -9 to 9.
+const CHANGES: usize = 19;
+const SEQUENCES: usize = CHANGES * CHANGES * CHANGES * CHANGES;
+
+fn next_secret(mut secret: u64) -> u64 {
+    secret = ((secret * 64) ^ secret) % PRUNE;
+    secret = ((secret / 32) ^ secret) % PRUNE;
+    secret = ((secret * 2048) ^ secret) % PRUNE;
+
+    secret
+}
+
+pub struct A;
+
+impl Puzzle for A {
+    type Input = Vec<u64>;
+    type Output = u64;
+
+    fn example_input() -> Self::Input {
+        vec![1, 10, 100, 2024]
+    }
+
+    fn example_output() -> Self::Output {
+        37327623
+    }
+
+    fn input_file() -> &'static str {
+        "inputs/day22/input"
+    }
+
+    fn parse_input<B>(reader: B) -> Result<Self::Input>
+    where
+        B: BufRead,
+    {
+        let mut secrets = Vec::new();
+
+        for line in reader.lines() {
+            let line = line?;
+
+            if !line.is_empty() {
+                secrets.push(line.parse()?);
+            }
+        }
+
+        Ok(secrets)
+    }
+
+    fn solve(&mut self, secrets: Self::Input) -> Result<Self::Output> {
+        Ok(secrets
+            .into_iter()
+            .map(|secret| (0..ROUNDS).fold(secret, |secret, _| next_secret(secret)))
+            .sum())
+    }
+}
+
+pub struct B;
+
+impl Puzzle for B {
+    type Input = Vec<u64>;
+    type Output = u32;
+
+    fn example_input() -> Self::Input {
+        vec![1, 2, 3, 2024]
+    }
+
+    fn example_output() -> Self::Output {
+        23
+    }
+
+    fn input_file() -> &'static str {
+        "inputs/day22/input"
+    }
+
+    fn parse_input<B>(reader: B) -> Result<Self::Input>
+    where
+        B: BufRead,
+    {
+        A::parse_input(reader)
+    }
+
+    fn solve(&mut self, secrets: Self::Input) -> Result<Self::Output> {
+        // the four most recent changes, encoded as a base-19 number, index these arrays
+        let mut bananas = vec![0u32; SEQUENCES];
+        let mut last_buyer = vec![usize::MAX; SEQUENCES];
+
+        for (buyer, mut secret) in secrets.into_iter().enumerate() {
+            let mut price = secret % 10;
+            let mut sequence = 0;
+
+            for round in 0..ROUNDS {
+                secret = next_secret(secret);
+
+                let next_price = secret % 10;
+                let change = (next_price + 9 - price) as usize;
+                price = next_price;
+
+                sequence = (sequence * CHANGES + change) % SEQUENCES;
+
+                // only the first occurrence of a sequence counts, the monkey sells right away
+                if round >= 3 && last_buyer[sequence] != buyer {
+                    last_buyer[sequence] = buyer;
+                    bananas[sequence] += price as u32;
+                }
+            }
+        }
+
+        Ok(bananas.into_iter().max().unwrap_or(0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a() -> Result<()> {
+        let mut a = A;
+        a.test_example()
+    }
+
+    #[test]
+    fn secrets() {
+        let mut secret = 123;
+        let expected = [15887950, 16495136, 527345, 704524, 1553684];
+
+        for e in expected {
+            secret = next_secret(secret);
+            assert_eq!(secret, e);
+        }
+    }
+
+    #[test]
+    fn b() -> Result<()> {
+        let mut b = B;
+        b.test_example()
+    }
+}
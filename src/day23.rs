@@ -0,0 +1,212 @@
+use crate::prelude::*;
+use std::collections::{HashMap, HashSet};
+use std::io::BufReader;
+
+pub struct Network {
+    names: Vec<String>,
+    neighbours: Vec<HashSet<usize>>,
+}
+
+impl Network {
+    fn node(&mut self, indices: &mut HashMap<String, usize>, name: &str) -> usize {
+        *indices.entry(name.to_owned()).or_insert_with(|| {
+            self.names.push(name.to_owned());
+            self.neighbours.push(HashSet::new());
+            self.names.len() - 1
+        })
+    }
+
+    fn triangles(&self) -> Vec<[usize; 3]> {
+        let mut triangles = Vec::new();
+
+        for (a, neighbours) in self.neighbours.iter().enumerate() {
+            for &b in neighbours.iter().filter(|&&b| b > a) {
+                for &c in self.neighbours[b].iter().filter(|&&c| c > b) {
+                    if neighbours.contains(&c) {
+                        triangles.push([a, b, c]);
+                    }
+                }
+            }
+        }
+
+        triangles
+    }
+
+    /// Bron–Kerbosch with pivoting: `r` is the clique being built, `p` the nodes that could still
+    /// extend it and `x` the ones that have already been tried.
+    fn bron_kerbosch(
+        &self,
+        r: &mut Vec<usize>,
+        mut p: HashSet<usize>,
+        mut x: HashSet<usize>,
+        best: &mut Vec<usize>,
+    ) {
+        if p.is_empty() && x.is_empty() {
+            if r.len() > best.len() {
+                *best = r.clone();
+            }
+
+            return;
+        }
+
+        // any maximal clique contains either the pivot or one of its non-neighbours
+        let pivot = *p
+            .union(&x)
+            .max_by_key(|&&u| self.neighbours[u].intersection(&p).count())
+            .unwrap();
+
+        let candidates: Vec<usize> = p.difference(&self.neighbours[pivot]).copied().collect();
+
+        for v in candidates {
+            let neighbours = &self.neighbours[v];
+
+            r.push(v);
+            self.bron_kerbosch(
+                r,
+                p.intersection(neighbours).copied().collect(),
+                x.intersection(neighbours).copied().collect(),
+                best,
+            );
+            r.pop();
+
+            p.remove(&v);
+            x.insert(v);
+        }
+    }
+
+    fn maximum_clique(&self) -> Vec<usize> {
+        let mut best = Vec::new();
+
+        self.bron_kerbosch(
+            &mut Vec::new(),
+            (0..self.names.len()).collect(),
+            HashSet::new(),
+            &mut best,
+        );
+
+        best
+    }
+}
+
+pub struct A;
+
+impl Puzzle for A {
+    type Input = Network;
+    type Output = usize;
+
+    fn example_input() -> Self::Input {
+        let input_str = "kh-tc\nqp-kh\nde-cg\nka-co\nyn-aq\nqp-ub\ncg-tb\nvc-aq\n\
+                         tb-ka\nwh-tc\nyn-cg\nkh-ub\nta-co\nde-co\ntc-td\ntb-wq\n\
+                         wh-td\nta-ka\ntd-qp\naq-cg\nwq-ub\nub-vc\nde-ta\nwq-aq\n\
+                         wq-vc\nwh-yn\nka-de\nkh-ta\nco-tc\nwh-qp\ntb-vc\ntd-yn"
+            .to_owned();
+
+        let reader = BufReader::new(input_str.as_bytes());
+        Self::parse_input(reader).unwrap()
+    }
+
+    fn example_output() -> Self::Output {
+        7
+    }
+
+    fn input_file() -> &'static str {
+        "inputs/day23/input"
+    }
+
+    fn parse_input<B>(reader: B) -> Result<Self::Input>
+    where
+        B: BufRead,
+    {
+        let mut network = Network {
+            names: Vec::new(),
+            neighbours: Vec::new(),
+        };
+
+        let mut indices = HashMap::new();
+
+        for line in reader.lines() {
+            let line = line?;
+
+            if line.is_empty() {
+                continue;
+            }
+
+            let (a, b) = line.split_once('-').ok_or("invalid connection")?;
+
+            let a = network.node(&mut indices, a);
+            let b = network.node(&mut indices, b);
+
+            network.neighbours[a].insert(b);
+            network.neighbours[b].insert(a);
+        }
+
+        Ok(network)
+    }
+
+    fn solve(&mut self, network: Self::Input) -> Result<Self::Output> {
+        Ok(network
+            .triangles()
+            .iter()
+            .filter(|triangle| {
+                triangle
+                    .iter()
+                    .any(|&node| network.names[node].starts_with('t'))
+            })
+            .count())
+    }
+}
+
+pub struct B;
+
+impl Puzzle for B {
+    type Input = Network;
+    type Output = String;
+
+    fn example_input() -> Self::Input {
+        A::example_input()
+    }
+
+    fn example_output() -> Self::Output {
+        "co,de,ka,ta".to_owned()
+    }
+
+    fn input_file() -> &'static str {
+        "inputs/day23/input"
+    }
+
+    fn parse_input<B>(reader: B) -> Result<Self::Input>
+    where
+        B: BufRead,
+    {
+        A::parse_input(reader)
+    }
+
+    fn solve(&mut self, network: Self::Input) -> Result<Self::Output> {
+        let mut password: Vec<&str> = network
+            .maximum_clique()
+            .into_iter()
+            .map(|node| network.names[node].as_str())
+            .collect();
+
+        password.sort();
+
+        Ok(password.join(","))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a() -> Result<()> {
+        let mut a = A;
+        a.test_example()
+    }
+
+    #[test]
+    fn b() -> Result<()> {
+        let mut b = B;
+        b.test_example()
+    }
+}
@@ -0,0 +1,357 @@
+use crate::prelude::*;
+use std::collections::{HashMap, HashSet};
+use std::io::BufReader;
+use std::str::FromStr;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Operation {
+    And,
+    Or,
+    Xor,
+}
+
+impl Operation {
+    fn apply(self, a: bool, b: bool) -> bool {
+        match self {
+            Self::And => a && b,
+            Self::Or => a || b,
+            Self::Xor => a ^ b,
+        }
+    }
+}
+
+impl FromStr for Operation {
+    type Err = crate::Error;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "AND" => Ok(Self::And),
+            "OR" => Ok(Self::Or),
+            "XOR" => Ok(Self::Xor),
+            _ => Err("invalid operation".into()),
+        }
+    }
+}
+
+pub struct Gate {
+    left: String,
+    operation: Operation,
+    right: String,
+    output: String,
+}
+
+impl Gate {
+    fn has_input_prefix(&self, prefixes: &[char]) -> bool {
+        [&self.left, &self.right]
+            .iter()
+            .all(|wire| wire.starts_with(prefixes))
+    }
+
+    fn has_input(&self, wire: &str) -> bool {
+        self.left == wire || self.right == wire
+    }
+}
+
+impl FromStr for Gate {
+    type Err = crate::Error;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let mut split = s.split_whitespace();
+
+        let left = split.next().ok_or("invalid gate")?.to_owned();
+        let operation = split.next().ok_or("invalid gate")?.parse()?;
+        let right = split.next().ok_or("invalid gate")?.to_owned();
+        let output = split.nth(1).ok_or("invalid gate")?.to_owned();
+
+        Ok(Self {
+            left,
+            operation,
+            right,
+            output,
+        })
+    }
+}
+
+pub struct Circuit {
+    wires: HashMap<String, bool>,
+    gates: Vec<Gate>,
+}
+
+impl Circuit {
+    fn evaluate(&self) -> Result<u64> {
+        let mut wires = self.wires.clone();
+        let mut pending: Vec<&Gate> = self.gates.iter().collect();
+
+        while !pending.is_empty() {
+            let before = pending.len();
+
+            pending.retain(
+                |gate| match (wires.get(&gate.left), wires.get(&gate.right)) {
+                    (Some(&a), Some(&b)) => {
+                        wires.insert(gate.output.clone(), gate.operation.apply(a, b));
+                        false
+                    }
+                    _ => true,
+                },
+            );
+
+            if pending.len() == before {
+                return Err("circuit contains a loop or an unconnected wire".into());
+            }
+        }
+
+        let mut z = 0;
+
+        for (wire, &value) in &wires {
+            if let Some(bit) = wire.strip_prefix('z') {
+                if value {
+                    z |= 1 << bit.parse::<u32>()?;
+                }
+            }
+        }
+
+        Ok(z)
+    }
+
+    /// Outputs of gates that don't fit into a ripple-carry adder. In such an adder, bit `i` is
+    ///
+    /// ```text
+    /// s_i = x_i XOR y_i      z_i = s_i XOR c_{i-1}
+    /// a_i = x_i AND y_i      b_i = s_i AND c_{i-1}      c_i = a_i OR b_i
+    /// ```
+    ///
+    /// except for bit 0, which is a half adder, and the final carry, which is the highest `z`.
+    fn misplaced_outputs(&self) -> HashSet<&str> {
+        let last_z = self
+            .gates
+            .iter()
+            .map(|gate| &gate.output)
+            .filter(|wire| wire.starts_with('z'))
+            .max();
+
+        let feeds = |wire: &str, operation: Operation| {
+            self.gates
+                .iter()
+                .any(|gate| gate.operation == operation && gate.has_input(wire))
+        };
+
+        let mut wrong = HashSet::new();
+
+        for gate in &self.gates {
+            let output = gate.output.as_str();
+            let from_inputs = gate.has_input_prefix(&['x', 'y']);
+            let first_bit = gate.has_input("x00");
+
+            let misplaced = match gate.operation {
+                // every z but the final carry is the XOR of a sum and a carry
+                _ if output.starts_with('z') && Some(&gate.output) != last_z => {
+                    gate.operation != Operation::Xor
+                }
+
+                // the final carry comes from an OR, like every other carry
+                _ if Some(&gate.output) == last_z => gate.operation != Operation::Or,
+
+                // a half sum is combined with the carry by another XOR
+                Operation::Xor if from_inputs => !first_bit && !feeds(output, Operation::Xor),
+
+                // any other XOR produces a z
+                Operation::Xor => true,
+
+                // both ANDs feed the OR building the next carry
+                Operation::And => !first_bit && !feeds(output, Operation::Or),
+
+                Operation::Or => false,
+            };
+
+            if misplaced {
+                wrong.insert(output);
+            }
+        }
+
+        wrong
+    }
+}
+
+pub struct A;
+
+impl Puzzle for A {
+    type Input = Circuit;
+    type Output = u64;
+
+    fn example_input() -> Self::Input {
+        let input_str = "x00: 1\n\
+                         x01: 1\n\
+                         x02: 1\n\
+                         y00: 0\n\
+                         y01: 1\n\
+                         y02: 0\n\
+                         \n\
+                         x00 AND y00 -> z00\n\
+                         x01 XOR y01 -> z01\n\
+                         x02 OR y02 -> z02"
+            .to_owned();
+
+        let reader = BufReader::new(input_str.as_bytes());
+        Self::parse_input(reader).unwrap()
+    }
+
+    fn example_output() -> Self::Output {
+        4
+    }
+
+    fn input_file() -> &'static str {
+        "inputs/day24/input"
+    }
+
+    fn parse_input<B>(reader: B) -> Result<Self::Input>
+    where
+        B: BufRead,
+    {
+        let mut wires = HashMap::new();
+        let mut gates = Vec::new();
+
+        for line in reader.lines() {
+            let line = line?;
+
+            if let Some((wire, value)) = line.split_once(": ") {
+                wires.insert(wire.to_owned(), value == "1");
+            } else if !line.is_empty() {
+                gates.push(line.parse()?);
+            }
+        }
+
+        Ok(Circuit { wires, gates })
+    }
+
+    fn solve(&mut self, circuit: Self::Input) -> Result<Self::Output> {
+        circuit.evaluate()
+    }
+}
+
+pub struct B;
+
+impl Puzzle for B {
+    type Input = Circuit;
+    type Output = String;
+
+    fn example_input() -> Self::Input {
+        // the puzzle has no example for this part, so build a 40 bit ripple-carry adder and swap
+        // some outputs in it
+        let swaps = [
+            ("z10", "c10"),
+            ("z15", "b15"),
+            ("a20", "s20"),
+            ("z30", "a30"),
+        ];
+
+        let swapped = |wire: String| {
+            for (a, b) in swaps {
+                if wire == a {
+                    return b.to_owned();
+                } else if wire == b {
+                    return a.to_owned();
+                }
+            }
+
+            wire
+        };
+
+        let bits = 40;
+        let mut lines = Vec::new();
+
+        for i in 0..bits {
+            lines.push(format!("x{i:02}: 0"));
+            lines.push(format!("y{i:02}: 0"));
+        }
+
+        lines.push(String::new());
+
+        let mut gate = |left: String, operation: &str, right: String, output: String| {
+            lines.push(format!("{left} {operation} {right} -> {}", swapped(output)));
+        };
+
+        gate("x00".into(), "XOR", "y00".into(), "z00".into());
+        gate("x00".into(), "AND", "y00".into(), "c00".into());
+
+        for i in 1..bits {
+            let carry = if i == bits - 1 {
+                format!("z{bits:02}")
+            } else {
+                format!("c{i:02}")
+            };
+
+            gate(
+                format!("x{i:02}"),
+                "XOR",
+                format!("y{i:02}"),
+                format!("s{i:02}"),
+            );
+            gate(
+                format!("x{i:02}"),
+                "AND",
+                format!("y{i:02}"),
+                format!("a{i:02}"),
+            );
+            gate(
+                format!("s{i:02}"),
+                "XOR",
+                format!("c{:02}", i - 1),
+                format!("z{i:02}"),
+            );
+            gate(
+                format!("s{i:02}"),
+                "AND",
+                format!("c{:02}", i - 1),
+                format!("b{i:02}"),
+            );
+            gate(format!("a{i:02}"), "OR", format!("b{i:02}"), carry);
+        }
+
+        let input_str = lines.join("\n");
+        let reader = BufReader::new(input_str.as_bytes());
+        Self::parse_input(reader).unwrap()
+    }
+
+    fn example_output() -> Self::Output {
+        "a20,a30,b15,c10,s20,z10,z15,z30".to_owned()
+    }
+
+    fn input_file() -> &'static str {
+        "inputs/day24/input"
+    }
+
+    fn parse_input<B>(reader: B) -> Result<Self::Input>
+    where
+        B: BufRead,
+    {
+        A::parse_input(reader)
+    }
+
+    fn solve(&mut self, circuit: Self::Input) -> Result<Self::Output> {
+        let mut wrong: Vec<&str> = circuit.misplaced_outputs().into_iter().collect();
+        wrong.sort();
+
+        if wrong.len() != 8 {
+            return Err(format!("expected 8 swapped wires, found {}", wrong.len()).into());
+        }
+
+        Ok(wrong.join(","))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a() -> Result<()> {
+        let mut a = A;
+        a.test_example()
+    }
+
+    #[test]
+    fn b() -> Result<()> {
+        let mut b = B;
+        b.test_example()
+    }
+}
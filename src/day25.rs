@@ -0,0 +1,125 @@
+use crate::prelude::*;
+use std::io::BufReader;
+
+const COLUMNS: usize = 5;
+const ROWS: usize = 7;
+
+pub struct Schematics {
+    locks: Vec<[u8; COLUMNS]>,
+    keys: Vec<[u8; COLUMNS]>,
+}
+
+impl Schematics {
+    fn add(&mut self, rows: &[Vec<char>]) -> Result<()> {
+        if rows.len() != ROWS || rows.iter().any(|row| row.len() != COLUMNS) {
+            return Err("schematic is not 7x5".into());
+        }
+
+        // the full top and bottom rows aren't counted as part of the heights
+        let mut heights = [0; COLUMNS];
+
+        for row in &rows[1..ROWS - 1] {
+            for (height, &c) in heights.iter_mut().zip(row) {
+                if c == '#' {
+                    *height += 1;
+                }
+            }
+        }
+
+        if rows[0].iter().all(|&c| c == '#') {
+            self.locks.push(heights);
+        } else {
+            self.keys.push(heights);
+        }
+
+        Ok(())
+    }
+}
+
+pub struct A;
+
+impl Puzzle for A {
+    type Input = Schematics;
+    type Output = usize;
+
+    fn example_input() -> Self::Input {
+        let input_str = "#####\n.####\n.####\n.####\n.#.#.\n.#...\n.....\n\
+                         \n\
+                         #####\n##.##\n.#.##\n...##\n...#.\n...#.\n.....\n\
+                         \n\
+                         .....\n#....\n#....\n#...#\n#.#.#\n#.###\n#####\n\
+                         \n\
+                         .....\n.....\n#.#..\n###..\n###.#\n###.#\n#####\n\
+                         \n\
+                         .....\n.....\n.....\n#....\n#.#..\n#.#.#\n#####"
+            .to_owned();
+
+        let reader = BufReader::new(input_str.as_bytes());
+        Self::parse_input(reader).unwrap()
+    }
+
+    fn example_output() -> Self::Output {
+        3
+    }
+
+    fn input_file() -> &'static str {
+        "inputs/day25/input"
+    }
+
+    fn parse_input<B>(reader: B) -> Result<Self::Input>
+    where
+        B: BufRead,
+    {
+        let mut schematics = Schematics {
+            locks: Vec::new(),
+            keys: Vec::new(),
+        };
+
+        let mut rows = Vec::new();
+
+        for line in reader.lines() {
+            let line = line?;
+
+            if line.is_empty() {
+                if !rows.is_empty() {
+                    schematics.add(&rows)?;
+                    rows.clear();
+                }
+            } else {
+                rows.push(line.chars().collect());
+            }
+        }
+
+        if !rows.is_empty() {
+            schematics.add(&rows)?;
+        }
+
+        Ok(schematics)
+    }
+
+    fn solve(&mut self, schematics: Self::Input) -> Result<Self::Output> {
+        let space = (ROWS - 2) as u8;
+        let mut count = 0;
+
+        for lock in &schematics.locks {
+            for key in &schematics.keys {
+                if lock.iter().zip(key).all(|(l, k)| l + k <= space) {
+                    count += 1;
+                }
+            }
+        }
+
+        Ok(count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a() -> Result<()> {
+        let mut a = A;
+        a.test_example()
+    }
+}
@@ -6,7 +6,21 @@ mod day05;
 mod day06;
 mod day07;
 mod day08;
+mod day14;
+mod day15;
+mod day16;
+mod day17;
+mod day18;
+mod day19;
+mod day20;
+mod day21;
+mod day22;
+mod day23;
+mod day24;
+mod day25;
 mod puzzle;
+#[cfg(test)]
+mod testing;
 
 pub type Error = Box<dyn std::error::Error>;
 pub type Result<T> = std::result::Result<T, Error>;
@@ -25,12 +39,22 @@ fn main() -> Result<()> {
         .ok_or("specify a day!")?
         .parse::<u32>()?;
 
+    let explain = match std::env::args().nth(2).as_deref() {
+        None => false,
+        Some("explain") => true,
+        Some(_) => return Err("unknown option, expected \"explain\"".into()),
+    };
+
+    if explain && day != 2 && day != 5 {
+        return Err("explain is only available for days 2 and 5".into());
+    }
+
     match day {
         1 => day01(),
-        2 => day02(),
+        2 => day02(explain),
         3 => day03(),
         4 => day04(),
-        5 => day05(),
+        5 => day05(explain),
         6 => day06(),
         7 => day07(),
         8 => day08(),
@@ -65,7 +89,15 @@ fn day01() -> Result<()> {
     Ok(())
 }
 
-fn day02() -> Result<()> {
+fn day02(explain: bool) -> Result<()> {
+    if explain {
+        for (i, diagnosis) in day02::explain()?.iter().enumerate() {
+            println!("report {}: {diagnosis}", i + 1);
+        }
+
+        return Ok(());
+    }
+
     let mut a = day02::A;
     println!("part A: {}", a.solution()?);
 
@@ -95,7 +127,25 @@ fn day04() -> Result<()> {
     Ok(())
 }
 
-fn day05() -> Result<()> {
+fn day05(explain: bool) -> Result<()> {
+    if explain {
+        let day05::Explanation { cycle, audits } = day05::explain()?;
+
+        match cycle {
+            Some(cycle) => println!("{cycle}"),
+            None => println!("ordering rules contain no cycles"),
+        }
+
+        for audit in &audits {
+            println!("{audit}");
+        }
+
+        let sum: u32 = audits.iter().map(day05::Audit::middle_page).sum();
+        println!("sum of middle pages: {sum}");
+
+        return Ok(());
+    }
+
     let mut a = day05::A;
     println!("part A: {}", a.solution()?);
 
@@ -156,49 +206,123 @@ fn day13() -> Result<()> {
 }
 
 fn day14() -> Result<()> {
-    todo!()
+    let mut a = day14::A::default();
+    println!("part A: {}", a.solution()?);
+
+    let mut b = day14::B::default();
+    println!("part B: {}", b.solution()?);
+
+    if let Some(picture) = b.picture() {
+        println!("{picture}");
+    }
+
+    Ok(())
 }
 
 fn day15() -> Result<()> {
-    todo!()
+    let mut a = day15::A;
+    println!("part A: {}", a.solution()?);
+
+    let mut b = day15::B;
+    println!("part B: {}", b.solution()?);
+
+    Ok(())
 }
 
 fn day16() -> Result<()> {
-    todo!()
+    let mut a = day16::A;
+    println!("part A: {}", a.solution()?);
+
+    let mut b = day16::B;
+    println!("part B: {}", b.solution()?);
+
+    Ok(())
 }
 
 fn day17() -> Result<()> {
-    todo!()
+    let mut a = day17::A;
+    println!("part A: {}", a.solution()?);
+
+    let mut b = day17::B;
+    println!("part B: {}", b.solution()?);
+
+    Ok(())
 }
 
 fn day18() -> Result<()> {
-    todo!()
+    let mut a = day18::A::default();
+    println!("part A: {}", a.solution()?);
+
+    let mut b = day18::B::default();
+    println!("part B: {}", b.solution()?);
+
+    Ok(())
 }
 
 fn day19() -> Result<()> {
-    todo!()
+    let mut a = day19::A;
+    println!("part A: {}", a.solution()?);
+
+    let mut b = day19::B;
+    println!("part B: {}", b.solution()?);
+
+    Ok(())
 }
 
 fn day20() -> Result<()> {
-    todo!()
+    let mut a = day20::A::default();
+    println!("part A: {}", a.solution()?);
+
+    let mut b = day20::B::default();
+    println!("part B: {}", b.solution()?);
+
+    Ok(())
 }
 
 fn day21() -> Result<()> {
-    todo!()
+    let mut a = day21::A;
+    println!("part A: {}", a.solution()?);
+
+    let mut b = day21::B;
+    println!("part B: {}", b.solution()?);
+
+    Ok(())
 }
 
 fn day22() -> Result<()> {
-    todo!()
+    let mut a = day22::A;
+    println!("part A: {}", a.solution()?);
+
+    let mut b = day22::B;
+    println!("part B: {}", b.solution()?);
+
+    Ok(())
 }
 
 fn day23() -> Result<()> {
-    todo!()
+    let mut a = day23::A;
+    println!("part A: {}", a.solution()?);
+
+    let mut b = day23::B;
+    println!("part B: {}", b.solution()?);
+
+    Ok(())
 }
 
 fn day24() -> Result<()> {
-    todo!()
+    let mut a = day24::A;
+    println!("part A: {}", a.solution()?);
+
+    let mut b = day24::B;
+    println!("part B: {}", b.solution()?);
+
+    Ok(())
 }
 
 fn day25() -> Result<()> {
-    todo!()
+    // there's no second part on the last day
+    let mut a = day25::A;
+    println!("part A: {}", a.solution()?);
+
+    Ok(())
 }
@@ -0,0 +1,35 @@
+//! Helpers shared by the tests.
+//!
+//! Benchmarks are `#[ignore]`d tests named `*_benchmark` that print their timings; run them with
+//! `cargo test --release -- --ignored --nocapture`.
+
+use std::time::{Duration, Instant};
+
+/// A linear congruential generator, so generated inputs are the same on every run.
+pub struct Random {
+    state: u64,
+}
+
+impl Random {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    /// A pseudo-random number below `modulus`.
+    pub fn below(&mut self, modulus: u64) -> u64 {
+        self.state = self
+            .state
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+
+        // the low bits of an LCG repeat with short periods
+        (self.state >> 33) % modulus
+    }
+}
+
+/// Runs `f`, returning its result along with how long it took.
+pub fn time<T>(f: impl FnOnce() -> T) -> (T, Duration) {
+    let start = Instant::now();
+    let result = f();
+    (result, start.elapsed())
+}